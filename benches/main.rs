@@ -1,3 +1,5 @@
+#![allow(clippy::unnecessary_cast)]
+
 use bencher::Bencher;
 
 use integer_encoding::*;
@@ -113,7 +115,166 @@ fn decode_v(b: &mut Bencher) {
     });
 }
 
-bencher::benchmark_group!(varint_benches, encode_v, decode_v);
+fn u128s() -> [u128; 8] {
+    [
+        0x0001_2345_6789_ABCD_EF01_2345_6789_ABCD,
+        0x9494_9291_9911_9074_5613_8239_2319_8123,
+        0x0000_0000_0000_0000_FFFF_FFFF_FFFF_FFFF,
+        u128::MAX,
+        0x0000_0001_0000_0000_0000_0000_0000_0000,
+        0x2595_8622_6822_5688_5221_2312_3000_9321,
+        0x0000_0000_0000_0100_0000_0000_0000_0000,
+        0x4012_9413_4012_5654_6544_8488_4888_4210,
+    ]
+}
+
+fn i128s() -> [i128; 8] {
+    [
+        -0x0001_2345_6789_ABCD_EF01_2345_6789_ABCD,
+        0x1494_9291_9911_9074_5613_8239_2319_8123,
+        -0x0000_0000_0000_0000_FFFF_FFFF_FFFF_FFFF,
+        i128::MIN,
+        i128::MAX,
+        -0x2595_8622_6822_5688_5221_2312_3000_9321,
+        0x0000_0000_0000_0100_0000_0000_0000_0000,
+        -1,
+    ]
+}
+
+fn encode_v_128(b: &mut Bencher) {
+    let my_u128s = u128s();
+    let my_i128s = i128s();
+    let mut dst = [0_u8; 19];
+
+    b.iter(|| {
+        for v in &my_u128s {
+            v.encode_var(&mut dst);
+        }
+        for v in &my_i128s {
+            v.encode_var(&mut dst);
+        }
+    });
+}
+
+fn decode_v_128(b: &mut Bencher) {
+    let u128_src: Vec<Vec<u8>> = u128s().iter().map(|v| v.encode_var_vec()).collect();
+    let i128_src: Vec<Vec<u8>> = i128s().iter().map(|v| v.encode_var_vec()).collect();
+
+    b.iter(|| {
+        for src in &u128_src {
+            u128::decode_var(src).unwrap();
+        }
+        for src in &i128_src {
+            i128::decode_var(src).unwrap();
+        }
+    });
+}
+
+/// A column of integers of mixed magnitude, for the slice benchmarks.
+fn u64_column() -> Vec<u64> {
+    (0..1024_u64)
+        .map(|i| i.wrapping_mul(0x9E3779B97F4A7C15) >> (i % 64))
+        .collect()
+}
+
+fn encode_v_loop(b: &mut Bencher) {
+    let src = u64_column();
+    let mut dst = Vec::new();
+    let mut buf = [0_u8; 10];
+
+    b.iter(|| {
+        dst.clear();
+        for v in &src {
+            let size = v.encode_var(&mut buf);
+            dst.extend_from_slice(&buf[..size]);
+        }
+    });
+}
+
+fn encode_v_slice(b: &mut Bencher) {
+    let src = u64_column();
+    let mut dst = Vec::new();
+
+    b.iter(|| {
+        dst.clear();
+        encode_var_slice(&src, &mut dst);
+    });
+}
+
+// The decoding benchmarks decode into a new Vec each time, as when reading a column.
+
+fn decode_v_loop(b: &mut Bencher) {
+    let mut src = Vec::new();
+    encode_var_slice(&u64_column(), &mut src);
+
+    b.iter(|| {
+        let mut dst: Vec<u64> = Vec::new();
+        let mut i = 0;
+        while i < src.len() {
+            let (v, size) = u64::try_decode_var(&src[i..]).unwrap();
+            dst.push(v);
+            i += size;
+        }
+        dst
+    });
+}
+
+fn decode_v_slice(b: &mut Bencher) {
+    let mut src = Vec::new();
+    encode_var_slice(&u64_column(), &mut src);
+
+    b.iter(|| {
+        let mut dst: Vec<u64> = Vec::new();
+        decode_var_slice(&src, &mut dst).unwrap();
+        dst
+    });
+}
+
+/// A column of small integers, most of which are encoded as single bytes.
+fn small_column() -> Vec<u32> {
+    (0..1024_u32).map(|i| i % 200).collect()
+}
+
+fn decode_v_small_loop(b: &mut Bencher) {
+    let mut src = Vec::new();
+    encode_var_slice(&small_column(), &mut src);
+
+    b.iter(|| {
+        let mut dst: Vec<u32> = Vec::new();
+        let mut i = 0;
+        while i < src.len() {
+            let (v, size) = u32::try_decode_var(&src[i..]).unwrap();
+            dst.push(v);
+            i += size;
+        }
+        dst
+    });
+}
+
+fn decode_v_small_slice(b: &mut Bencher) {
+    let mut src = Vec::new();
+    encode_var_slice(&small_column(), &mut src);
+
+    b.iter(|| {
+        let mut dst: Vec<u32> = Vec::new();
+        decode_var_slice(&src, &mut dst).unwrap();
+        dst
+    });
+}
+
+bencher::benchmark_group!(
+    varint_benches,
+    encode_v,
+    decode_v,
+    encode_v_128,
+    decode_v_128,
+    encode_v_loop,
+    encode_v_slice,
+    decode_v_loop,
+    decode_v_slice,
+    decode_v_small_loop,
+    decode_v_small_slice
+);
 
 fn encode_f(b: &mut Bencher) {
     let my_u64 = 94949291991190 as u64;
@@ -172,4 +333,78 @@ fn decode_f(b: &mut Bencher) {
 
 bencher::benchmark_group!(fixedint_benches, encode_f, decode_f);
 
-bencher::benchmark_main!(varint_benches, fixedint_benches);
+/// A column of `u32`s of mixed magnitude, for the group varint benchmarks.
+fn u32_column() -> Vec<u32> {
+    (0..1024_u32)
+        .map(|i| i.wrapping_mul(0x9E3779B9) >> (i % 32))
+        .collect()
+}
+
+fn encode_gv(b: &mut Bencher) {
+    let src = u32_column();
+    let mut dst = Vec::new();
+
+    b.iter(|| {
+        dst.clear();
+        encode_group_varint(&src, &mut dst);
+    });
+}
+
+fn decode_gv(b: &mut Bencher) {
+    let mut src = Vec::new();
+    encode_group_varint(&u32_column(), &mut src);
+    let mut dst: Vec<u32> = Vec::new();
+
+    b.iter(|| {
+        dst.clear();
+        decode_group_varint(&src, 1024, &mut dst).unwrap();
+    });
+}
+
+fn decode_v_u32_slice(b: &mut Bencher) {
+    let mut src = Vec::new();
+    encode_var_slice(&u32_column(), &mut src);
+    let mut dst: Vec<u32> = Vec::new();
+
+    b.iter(|| {
+        dst.clear();
+        decode_var_slice(&src, &mut dst).unwrap();
+    });
+}
+
+fn encode_svb(b: &mut Bencher) {
+    let src = u32_column();
+    let mut dst = Vec::new();
+
+    b.iter(|| {
+        dst.clear();
+        encode_stream_vbyte(&src, &mut dst);
+    });
+}
+
+fn decode_svb(b: &mut Bencher) {
+    let mut src = Vec::new();
+    encode_stream_vbyte(&u32_column(), &mut src);
+    let mut dst: Vec<u32> = Vec::new();
+
+    b.iter(|| {
+        dst.clear();
+        decode_stream_vbyte(&src, 1024, &mut dst).unwrap();
+    });
+}
+
+bencher::benchmark_group!(
+    group_varint_benches,
+    encode_gv,
+    decode_gv,
+    decode_v_u32_slice
+);
+
+bencher::benchmark_group!(stream_vbyte_benches, encode_svb, decode_svb);
+
+bencher::benchmark_main!(
+    varint_benches,
+    fixedint_benches,
+    group_varint_benches,
+    stream_vbyte_benches
+);
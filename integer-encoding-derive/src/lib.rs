@@ -0,0 +1,392 @@
+//! Derive macros for `integer_encoding::VarIntEncode` and `integer_encoding::VarIntDecode`.
+//!
+//! Use them through the `derive` feature of the `integer-encoding` crate, which re-exports them.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DataEnum, DeriveInput, Error, Expr, ExprLit, Field,
+    Fields, Generics, Ident, Lit,
+};
+
+/// How a single field is encoded.
+#[derive(Clone, Copy)]
+enum Encoding {
+    VarInt,
+    Fixed,
+    FixedBe,
+    /// No attribute: the field's type implements `VarIntEncode`/`VarIntDecode`, as all primitive
+    /// integers do (as varints).
+    Trait,
+}
+
+/// Reads the `#[encoding(...)]` attribute of a field.
+fn field_encoding(field: &Field) -> syn::Result<Encoding> {
+    let mut encoding = None;
+    for attr in &field.attrs {
+        if !attr.path().is_ident("encoding") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            let e = if meta.path.is_ident("varint") {
+                Encoding::VarInt
+            } else if meta.path.is_ident("fixed") {
+                Encoding::Fixed
+            } else if meta.path.is_ident("fixed_be") {
+                Encoding::FixedBe
+            } else {
+                return Err(meta.error("expected `varint`, `fixed` or `fixed_be`"));
+            };
+            if encoding.is_some() {
+                return Err(meta.error("only one encoding can be specified per field"));
+            }
+            encoding = Some(e);
+            Ok(())
+        })?;
+    }
+    Ok(encoding.unwrap_or(Encoding::Trait))
+}
+
+/// A field together with the identifier its value is bound to in the generated code.
+struct FieldInfo<'a> {
+    field: &'a Field,
+    binding: Ident,
+    encoding: Encoding,
+}
+
+fn field_infos(fields: &Fields) -> syn::Result<Vec<FieldInfo<'_>>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            Ok(FieldInfo {
+                field,
+                binding: format_ident!("__f{}", i),
+                encoding: field_encoding(field)?,
+            })
+        })
+        .collect()
+}
+
+/// Returns a pattern destructuring `path` (a struct or enum variant) into the fields' bindings.
+fn pattern(path: &TokenStream2, fields: &Fields, infos: &[FieldInfo]) -> TokenStream2 {
+    let bindings = infos.iter().map(|f| &f.binding);
+    match fields {
+        Fields::Named(_) => {
+            let names = infos.iter().map(|f| &f.field.ident);
+            quote!(#path { #(#names: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+        Fields::Unit => quote!(#path),
+    }
+}
+
+/// Returns an expression constructing `path` from the decoded fields.
+fn construct(path: &TokenStream2, fields: &Fields, infos: &[FieldInfo]) -> TokenStream2 {
+    let values = infos.iter().map(decode_field);
+    match fields {
+        Fields::Named(_) => {
+            let names = infos.iter().map(|f| &f.field.ident);
+            quote!(#path { #(#names: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#values),*)),
+        Fields::Unit => quote!(#path),
+    }
+}
+
+fn required_space_field(f: &FieldInfo) -> TokenStream2 {
+    let (v, ty) = (&f.binding, &f.field.ty);
+    match f.encoding {
+        Encoding::VarInt => quote!(::integer_encoding::VarInt::required_space(*#v)),
+        Encoding::Fixed | Encoding::FixedBe => {
+            quote!(<#ty as ::integer_encoding::FixedInt>::ENCODED_SIZE)
+        }
+        Encoding::Trait => quote!(::integer_encoding::VarIntEncode::required_space(#v)),
+    }
+}
+
+fn encode_field(f: &FieldInfo) -> TokenStream2 {
+    let v = &f.binding;
+    let written = match f.encoding {
+        Encoding::VarInt => quote!(::integer_encoding::VarInt::encode_var(*#v, &mut __dst[__i..])),
+        Encoding::Fixed => {
+            quote!(::integer_encoding::__private::encode_fixed(*#v, &mut __dst[__i..]))
+        }
+        Encoding::FixedBe => {
+            quote!(::integer_encoding::__private::encode_fixed_be(*#v, &mut __dst[__i..]))
+        }
+        Encoding::Trait => quote!(::integer_encoding::VarIntEncode::encode(#v, &mut __dst[__i..])),
+    };
+    quote!(__i += #written;)
+}
+
+fn decode_field(f: &FieldInfo) -> TokenStream2 {
+    let ty = &f.field.ty;
+    let decoded = match f.encoding {
+        Encoding::VarInt => {
+            quote!(<#ty as ::integer_encoding::VarInt>::try_decode_var(&__src[__i..]))
+        }
+        Encoding::Fixed => {
+            quote!(::integer_encoding::__private::decode_fixed::<#ty>(&__src[__i..]))
+        }
+        Encoding::FixedBe => {
+            quote!(::integer_encoding::__private::decode_fixed_be::<#ty>(&__src[__i..]))
+        }
+        Encoding::Trait => {
+            quote!(<#ty as ::integer_encoding::VarIntDecode>::decode(&__src[__i..]))
+        }
+    };
+    quote!({
+        let (__v, __n) = #decoded?;
+        __i += __n;
+        __v
+    })
+}
+
+/// Returns the index each variant of `e` is encoded as: its discriminant if given, otherwise one
+/// more than the previous variant's index (starting at 0).
+fn variant_indices(e: &DataEnum) -> syn::Result<Vec<u32>> {
+    let mut indices = Vec::with_capacity(e.variants.len());
+    let mut next = Some(0_u32);
+    for variant in &e.variants {
+        let index = match &variant.discriminant {
+            Some((_, expr)) => match expr {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(lit), ..
+                }) => lit.base10_parse::<u32>()?,
+                _ => {
+                    return Err(Error::new_spanned(
+                        expr,
+                        "discriminants must be integer literals fitting into u32",
+                    ))
+                }
+            },
+            None => next.ok_or_else(|| {
+                Error::new_spanned(variant, "the index of this variant doesn't fit into u32")
+            })?,
+        };
+        indices.push(index);
+        next = index.checked_add(1);
+    }
+    Ok(indices)
+}
+
+/// Returns `generics` with `bound` added to every type parameter, like the derives of the
+/// standard library do.
+fn add_trait_bounds(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+/// Derives `VarIntEncode`, encoding all fields in declaration order.
+///
+/// Fields are encoded according to their `#[encoding(...)]` attribute:
+///
+/// * `varint`: as `VarInt`. Signed integers use zigzag encoding.
+/// * `fixed`, `fixed_be`: as little-endian or big-endian `FixedInt`.
+///
+/// Fields without attribute are encoded using their type's `VarIntEncode` implementation. For
+/// primitive integers, however they are spelled (e.g. `core::primitive::u32` or an alias), this
+/// is the same as `varint`.
+///
+/// Enums are encoded as the index of the variant as `u32` varint, followed by the variant's
+/// fields. The index is the variant's discriminant if it is given (`A = 5`, which must be an
+/// integer literal fitting into `u32`), and otherwise one more than the previous variant's index,
+/// starting at 0; this matches how Rust numbers the variants of fieldless enums.
+///
+/// Type parameters are required to implement `VarIntEncode`.
+#[proc_macro_derive(VarIntEncode, attributes(encoding))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `VarIntDecode`, decoding the encoding written by the derived `VarIntEncode`. Fields
+/// must have the same `#[encoding(...)]` attributes as for `VarIntEncode`.
+///
+/// Type parameters are required to implement `VarIntDecode`.
+#[proc_macro_derive(VarIntDecode, attributes(encoding))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let (required_space, encode) = match &input.data {
+        Data::Struct(s) => {
+            let infos = field_infos(&s.fields)?;
+            let pat = pattern(&quote!(Self), &s.fields, &infos);
+            let spaces = infos.iter().map(required_space_field);
+            let encodes = infos.iter().map(encode_field);
+            (
+                quote!(match self { #pat => 0 #(+ #spaces)* }),
+                quote!(match self { #pat => { #(#encodes)* } }),
+            )
+        }
+        Data::Enum(e) => {
+            let mut space_arms = vec![];
+            let mut encode_arms = vec![];
+            for (index, variant) in variant_indices(e)?.into_iter().zip(&e.variants) {
+                let ident = &variant.ident;
+                let infos = field_infos(&variant.fields)?;
+                let pat = pattern(&quote!(Self::#ident), &variant.fields, &infos);
+                let spaces = infos.iter().map(required_space_field);
+                let encodes = infos.iter().map(encode_field);
+                space_arms.push(quote!(
+                    #pat => ::integer_encoding::VarInt::required_space(#index) #(+ #spaces)*
+                ));
+                encode_arms.push(quote!(#pat => {
+                    __i += ::integer_encoding::VarInt::encode_var(#index, &mut __dst[__i..]);
+                    #(#encodes)*
+                }));
+            }
+            if e.variants.is_empty() {
+                (quote!(match *self {}), quote!(match *self {}))
+            } else {
+                (
+                    quote!(match self { #(#space_arms,)* }),
+                    quote!(match self { #(#encode_arms)* }),
+                )
+            }
+        }
+        Data::Union(u) => {
+            return Err(Error::new(
+                u.union_token.span,
+                "VarIntEncode cannot be derived for unions",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let generics = add_trait_bounds(&input.generics, quote!(::integer_encoding::VarIntEncode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::integer_encoding::VarIntEncode for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn required_space(&self) -> usize {
+                #required_space
+            }
+
+            #[allow(unused_mut, unused_variables, unreachable_code)]
+            fn encode(&self, __dst: &mut [u8]) -> usize {
+                let mut __i = 0;
+                #encode
+                __i
+            }
+        }
+    })
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let decode = match &input.data {
+        Data::Struct(s) => {
+            let infos = field_infos(&s.fields)?;
+            construct(&quote!(Self), &s.fields, &infos)
+        }
+        Data::Enum(e) => {
+            let mut arms = vec![];
+            for (index, variant) in variant_indices(e)?.into_iter().zip(&e.variants) {
+                let ident = &variant.ident;
+                let infos = field_infos(&variant.fields)?;
+                let value = construct(&quote!(Self::#ident), &variant.fields, &infos);
+                arms.push(quote!(#index => #value,));
+            }
+            quote!({
+                let (__index, __n) = <u32 as ::integer_encoding::VarInt>::try_decode_var(__src)?;
+                __i += __n;
+                match __index {
+                    #(#arms)*
+                    index => {
+                        return ::core::result::Result::Err(
+                            ::integer_encoding::DecodeError::UnknownVariant { index },
+                        )
+                    }
+                }
+            })
+        }
+        Data::Union(u) => {
+            return Err(Error::new(
+                u.union_token.span,
+                "VarIntDecode cannot be derived for unions",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let generics = add_trait_bounds(&input.generics, quote!(::integer_encoding::VarIntDecode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::integer_encoding::VarIntDecode for #name #ty_generics #where_clause {
+            #[allow(unused_mut, unreachable_code)]
+            fn decode(
+                __src: &[u8],
+            ) -> ::core::result::Result<(Self, usize), ::integer_encoding::DecodeError> {
+                let mut __i = 0;
+                let __value = #decode;
+                ::core::result::Result::Ok((__value, __i))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_decode, expand_encode};
+
+    fn errors(input: &str) -> (String, String) {
+        let input = syn::parse_str(input).unwrap();
+        (
+            expand_encode(&input).unwrap_err().to_string(),
+            expand_decode(&input).unwrap_err().to_string(),
+        )
+    }
+
+    #[test]
+    fn test_unknown_encoding() {
+        let (encode, decode) = errors("struct S { #[encoding(zigzag)] a: i32 }");
+        assert_eq!(encode, "expected `varint`, `fixed` or `fixed_be`");
+        assert_eq!(decode, encode);
+    }
+
+    #[test]
+    fn test_duplicate_encoding() {
+        let (encode, _) = errors("struct S { #[encoding(fixed, varint)] a: u32 }");
+        assert_eq!(encode, "only one encoding can be specified per field");
+    }
+
+    #[test]
+    fn test_invalid_discriminants() {
+        for input in &[
+            "enum E { A = 1 + 1 }",
+            "enum E { A = -1 }",
+            "enum E { A = 4294967296 }",
+        ] {
+            let (encode, decode) = errors(input);
+            assert!(
+                encode.contains("u32") || encode.contains("number too large"),
+                "{}: {}",
+                input,
+                encode
+            );
+            assert_eq!(decode, encode);
+        }
+
+        let (encode, _) = errors("enum E { A = 4294967295, B }");
+        assert_eq!(encode, "the index of this variant doesn't fit into u32");
+    }
+
+    #[test]
+    fn test_union() {
+        let (encode, decode) = errors("union U { a: u32 }");
+        assert_eq!(encode, "VarIntEncode cannot be derived for unions");
+        assert_eq!(decode, "VarIntDecode cannot be derived for unions");
+    }
+}
@@ -0,0 +1,107 @@
+use integer_encoding::{DecodeError, VarIntDecode, VarIntEncode};
+
+fn roundtrip<T: VarIntEncode + VarIntDecode + PartialEq + std::fmt::Debug>(v: T) -> Vec<u8> {
+    let encoded = v.encode_vec();
+    assert_eq!(encoded.len(), v.required_space());
+    assert_eq!(T::decode(&encoded), Ok((v, encoded.len())));
+    encoded
+}
+
+#[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+struct Attributes {
+    plain: i32,
+    #[encoding(varint)]
+    varint: i32,
+    #[encoding(fixed)]
+    fixed: u32,
+    #[encoding(fixed_be)]
+    fixed_be: u32,
+}
+
+#[test]
+fn test_attributes() {
+    assert_eq!(
+        roundtrip(Attributes {
+            plain: -1,
+            varint: 300,
+            fixed: 0x01020304,
+            fixed_be: 0x01020304,
+        }),
+        vec![1, 0xD8, 0x04, 4, 3, 2, 1, 1, 2, 3, 4]
+    );
+}
+
+#[test]
+fn test_fixed_signed() {
+    #[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+    struct Signed(#[encoding(fixed)] i16, #[encoding(fixed_be)] i64);
+
+    let mut expected = vec![0xFE, 0xFF];
+    expected.extend_from_slice(&i64::MIN.to_be_bytes());
+    assert_eq!(roundtrip(Signed(-2, i64::MIN)), expected);
+}
+
+type Id = u64;
+
+#[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+struct Spelled {
+    id: Id,
+    a: core::primitive::u32,
+    b: std::primitive::u64,
+    c: ::core::primitive::i8,
+}
+
+#[test]
+fn test_integer_spellings() {
+    assert_eq!(
+        roundtrip(Spelled {
+            id: 300,
+            a: 1,
+            b: 2,
+            c: -1,
+        }),
+        vec![0xAC, 0x02, 1, 2, 1]
+    );
+}
+
+#[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+#[repr(u32)]
+enum Discriminants {
+    A,
+    B = 5,
+    C,
+    D(u8),
+    E = 300,
+}
+
+#[test]
+fn test_enum_discriminants() {
+    assert_eq!(roundtrip(Discriminants::A), vec![0]);
+    assert_eq!(roundtrip(Discriminants::B), vec![5]);
+    assert_eq!(roundtrip(Discriminants::C), vec![6]);
+    assert_eq!(roundtrip(Discriminants::D(9)), vec![7, 9]);
+    assert_eq!(roundtrip(Discriminants::E), vec![0xAC, 0x02]);
+
+    for index in &[1, 4, 8, 127] {
+        assert_eq!(
+            Discriminants::decode(&[*index]),
+            Err(DecodeError::UnknownVariant {
+                index: u32::from(*index)
+            })
+        );
+    }
+}
+
+#[test]
+fn test_enum_fieldless_discriminants_match_as_casts() {
+    #[derive(Debug, Clone, Copy, PartialEq, VarIntEncode, VarIntDecode)]
+    enum Level {
+        Low = 1,
+        Mid,
+        High = 10,
+    }
+
+    for level in &[Level::Low, Level::Mid, Level::High] {
+        assert_eq!(roundtrip(*level), vec![*level as u8]);
+    }
+}
@@ -0,0 +1,126 @@
+use bytes::{Buf, BufMut};
+
+use crate::error::DecodeError;
+use crate::fixed::FixedInt;
+use crate::varint::{VarInt, MAX_VARINT_SIZE, MSB};
+
+/// The largest `FixedInt` (`u128`/`i128`), in bytes.
+const MAX_FIXEDINT_SIZE: usize = 16;
+
+/// Extension trait for reading [`VarInt`]s from a [`bytes::Buf`].
+pub trait VarIntBuf {
+    /// Decodes a varint and advances the buffer past it. Varints spanning several chunks of a
+    /// non-contiguous buffer are supported.
+    ///
+    /// Like [`VarIntReader`](crate::VarIntReader), this reads at most
+    /// [`VI::varint_max_size()`](VarInt::varint_max_size) bytes: longer (overlong) encodings are
+    /// rejected with [`DecodeError::Overflow`].
+    ///
+    /// If the varint is contained in the buffer's first chunk, the buffer is not advanced on
+    /// error. Otherwise, the bytes examined before the error are consumed.
+    fn get_varint<VI: VarInt>(&mut self) -> Result<VI, DecodeError>;
+}
+
+/// Extension trait for writing [`VarInt`]s to a [`bytes::BufMut`].
+pub trait VarIntBufMut {
+    /// Encodes `n` as varint and writes it to the buffer. Returns the number of bytes written.
+    ///
+    /// Panics if the buffer doesn't have enough space, like the other `put_*` methods.
+    fn put_varint<VI: VarInt>(&mut self, n: VI) -> usize;
+}
+
+/// Extension trait for reading [`FixedInt`]s from a [`bytes::Buf`].
+pub trait FixedIntBuf {
+    /// Decodes a little-endian fixed integer and advances the buffer past it. If the buffer is too
+    /// short, [`DecodeError::UnexpectedEnd`] is returned and the buffer is not advanced.
+    fn get_fixedint<FI: FixedInt>(&mut self) -> Result<FI, DecodeError>;
+    /// Like `get_fixedint()`, but decodes a big-endian integer.
+    fn get_fixedint_be<FI: FixedInt>(&mut self) -> Result<FI, DecodeError>;
+}
+
+/// Extension trait for writing [`FixedInt`]s to a [`bytes::BufMut`].
+pub trait FixedIntBufMut {
+    /// Writes `n` in little-endian order to the buffer. Returns the number of bytes written.
+    ///
+    /// Panics if the buffer doesn't have enough space, like the other `put_*` methods.
+    fn put_fixedint<FI: FixedInt>(&mut self, n: FI) -> usize;
+    /// Like `put_fixedint()`, but writes `n` in big-endian order.
+    fn put_fixedint_be<FI: FixedInt>(&mut self, n: FI) -> usize;
+}
+
+impl<B: Buf> VarIntBuf for B {
+    fn get_varint<VI: VarInt>(&mut self) -> Result<VI, DecodeError> {
+        let max = VI::varint_max_size().min(MAX_VARINT_SIZE);
+
+        // Fast path: the varint is contained in the first chunk.
+        match VI::try_decode_var(self.chunk()) {
+            Ok((v, size)) if size <= max => {
+                self.advance(size);
+                return Ok(v);
+            }
+            Ok(_) => return Err(DecodeError::Overflow),
+            Err(DecodeError::UnexpectedEnd) if self.chunk().len() >= max => {
+                return Err(DecodeError::Overflow)
+            }
+            Err(DecodeError::UnexpectedEnd) if self.chunk().len() < self.remaining() => {}
+            Err(e) => return Err(e),
+        }
+
+        // Slow path: gather the varint's bytes from several chunks and decode them like the fast
+        // path does, so that the result doesn't depend on how the buffer is split.
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
+        let mut len = 0;
+        while len < max && self.has_remaining() {
+            buf[len] = self.get_u8();
+            len += 1;
+            if buf[len - 1] & MSB == 0 {
+                break;
+            }
+        }
+        if len == max && buf[len - 1] & MSB != 0 {
+            return Err(DecodeError::Overflow);
+        }
+        Ok(VI::try_decode_var(&buf[..len])?.0)
+    }
+}
+
+impl<B: BufMut> VarIntBufMut for B {
+    fn put_varint<VI: VarInt>(&mut self, n: VI) -> usize {
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
+        let used = n.encode_var(&mut buf);
+        self.put_slice(&buf[..used]);
+        used
+    }
+}
+
+impl<B: Buf> FixedIntBuf for B {
+    fn get_fixedint<FI: FixedInt>(&mut self) -> Result<FI, DecodeError> {
+        if self.remaining() < FI::ENCODED_SIZE {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let mut buf = [0_u8; MAX_FIXEDINT_SIZE];
+        self.copy_to_slice(&mut buf[..FI::ENCODED_SIZE]);
+        FI::try_decode_fixed(&buf[..FI::ENCODED_SIZE])
+    }
+
+    fn get_fixedint_be<FI: FixedInt>(&mut self) -> Result<FI, DecodeError> {
+        if self.remaining() < FI::ENCODED_SIZE {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let mut buf = [0_u8; MAX_FIXEDINT_SIZE];
+        self.copy_to_slice(&mut buf[..FI::ENCODED_SIZE]);
+        FI::try_decode_fixed_be(&buf[..FI::ENCODED_SIZE])
+    }
+}
+
+impl<B: BufMut> FixedIntBufMut for B {
+    fn put_fixedint<FI: FixedInt>(&mut self, n: FI) -> usize {
+        self.put_slice(n.encode_fixed_light().as_ref());
+        FI::ENCODED_SIZE
+    }
+
+    fn put_fixedint_be<FI: FixedInt>(&mut self, n: FI) -> usize {
+        self.put_slice(n.encode_fixed_light_be().as_ref());
+        FI::ENCODED_SIZE
+    }
+}
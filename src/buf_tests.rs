@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use bytes::{Buf, Bytes, BytesMut};
+
+    use crate::buf::{FixedIntBuf, FixedIntBufMut, VarIntBuf, VarIntBufMut};
+    use crate::error::DecodeError;
+    use crate::varint::VarInt;
+
+    #[test]
+    fn test_varint_roundtrip() {
+        let mut buf = BytesMut::new();
+        assert_eq!(buf.put_varint(300_u32), 2);
+        assert_eq!(buf.put_varint(-1_i64), 1);
+        assert_eq!(buf.put_varint(u128::MAX), 19);
+        assert_eq!(&buf[..3], &[0xAC, 0x02, 0x01]);
+
+        let mut buf = buf.freeze();
+        assert_eq!(buf.get_varint::<u32>(), Ok(300));
+        assert_eq!(buf.get_varint::<i64>(), Ok(-1));
+        assert_eq!(buf.get_varint::<u128>(), Ok(u128::MAX));
+        assert_eq!(buf.get_varint::<u32>(), Err(DecodeError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_varint_errors_dont_advance() {
+        let mut buf = Bytes::from_static(&[0xFF, 0xFF]);
+        assert_eq!(buf.get_varint::<u32>(), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(buf.remaining(), 2);
+
+        let mut buf = Bytes::from_static(&[0xFF, 0x03]);
+        assert_eq!(buf.get_varint::<u8>(), Err(DecodeError::OutOfRange));
+        assert_eq!(buf.remaining(), 2);
+    }
+
+    #[test]
+    fn test_varint_across_chunks() {
+        let mut encoded = [0_u8; 11];
+        let len = (i64::MIN + 3).encode_var(&mut encoded);
+        encoded[len] = 0x05;
+        for split in 0..=len {
+            let mut buf = (&encoded[..split]).chain(&encoded[split..=len]);
+            assert_eq!(buf.get_varint::<i64>(), Ok(i64::MIN + 3), "split {}", split);
+            assert_eq!(buf.get_varint::<u8>(), Ok(5));
+            assert!(!buf.has_remaining());
+        }
+
+        // Three chunks, the varint spanning all of them.
+        let mut buf = (&[0x80_u8][..]).chain(&[0x80_u8][..]).chain(&[0x01_u8][..]);
+        assert_eq!(buf.get_varint::<u32>(), Ok(1 << 14));
+
+        let mut buf = (&[0x80_u8][..]).chain(&[0x80_u8][..]);
+        assert_eq!(buf.get_varint::<u32>(), Err(DecodeError::UnexpectedEnd));
+
+        let mut buf = (&[0xFF_u8; 6][..]).chain(&[0xFF_u8; 6][..]);
+        assert_eq!(buf.get_varint::<u16>(), Err(DecodeError::Overflow));
+
+        // No more than `varint_max_size()` bytes are consumed.
+        let mut buf = (&[0xFF_u8][..]).chain(&[0xFF_u8, 0x01, 0x05][..]);
+        assert_eq!(buf.get_varint::<u8>(), Err(DecodeError::Overflow));
+        assert_eq!(buf.remaining(), 2);
+    }
+
+    #[test]
+    fn test_varint_split_independent() {
+        let inputs: [&[u8]; 6] = [
+            // Overlong; accepted by `try_decode_var()`, but longer than `varint_max_size()`.
+            &[0x80, 0x80, 0x80, 0x80, 0x80, 0x00],
+            &[0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00],
+            &[0xFF; 12],
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0x7F],
+            &[0x80, 0x80],
+            &[0xAC, 0x02],
+        ];
+        assert_eq!(
+            (&inputs[0][..]).get_varint::<u32>(),
+            Err(DecodeError::Overflow)
+        );
+        for input in &inputs {
+            let expected = (&input[..]).get_varint::<u32>();
+            for split in 0..=input.len() {
+                let mut buf = (&input[..split]).chain(&input[split..]);
+                assert_eq!(
+                    buf.get_varint::<u32>(),
+                    expected,
+                    "{:?} split {}",
+                    input,
+                    split
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixedint() {
+        let mut buf = BytesMut::new();
+        assert_eq!(buf.put_fixedint(0x0102_u16), 2);
+        assert_eq!(buf.put_fixedint_be(0x0102_u16), 2);
+        assert_eq!(buf.put_fixedint(-2_i64), 8);
+        assert_eq!(&buf[..4], &[2, 1, 1, 2]);
+
+        let mut buf = (&buf[..3]).chain(&buf[3..]);
+        assert_eq!(buf.get_fixedint::<u16>(), Ok(0x0102));
+        assert_eq!(buf.get_fixedint_be::<u16>(), Ok(0x0102));
+        assert_eq!(buf.get_fixedint::<i64>(), Ok(-2));
+        assert_eq!(buf.get_fixedint::<u8>(), Err(DecodeError::UnexpectedEnd));
+
+        let mut buf = Bytes::from_static(&[1, 2, 3]);
+        assert_eq!(buf.get_fixedint::<u32>(), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(
+            buf.get_fixedint_be::<u32>(),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(buf.remaining(), 3);
+    }
+}
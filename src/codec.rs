@@ -0,0 +1,116 @@
+use std::io;
+
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::error::DecodeError;
+use crate::varint::{VarInt, MAX_VARINT_SIZE};
+
+/// The default maximum frame length of a [`VarIntLengthCodec`], 8 MiB.
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// A [`tokio_util::codec`] `Decoder` and `Encoder` for frames prefixed by their length as
+/// [`VarInt`], e.g. for use with `Framed`.
+///
+/// This is the framing used by
+/// [`VarIntReader::read_length_delimited()`](crate::VarIntReader::read_length_delimited) and
+/// [`VarIntWriter::write_length_delimited()`](crate::VarIntWriter::write_length_delimited), and
+/// by protobuf for streams of messages. Frames longer than the maximum frame length are rejected
+/// with an [`io::ErrorKind::InvalidData`] error wrapping [`DecodeError::FrameTooLarge`], both when
+/// decoding and when encoding.
+#[derive(Debug, Clone)]
+pub struct VarIntLengthCodec {
+    max_frame_length: usize,
+    /// Length of the frame currently being read, once its prefix has been decoded.
+    frame_length: Option<usize>,
+}
+
+impl VarIntLengthCodec {
+    /// Creates a codec with a maximum frame length of [`DEFAULT_MAX_FRAME_LENGTH`].
+    pub fn new() -> VarIntLengthCodec {
+        VarIntLengthCodec::with_max_frame_length(DEFAULT_MAX_FRAME_LENGTH)
+    }
+
+    /// Creates a codec rejecting frames longer than `max_frame_length` bytes.
+    pub fn with_max_frame_length(max_frame_length: usize) -> VarIntLengthCodec {
+        VarIntLengthCodec {
+            max_frame_length,
+            frame_length: None,
+        }
+    }
+
+    /// Returns the maximum frame length.
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    fn check_frame_length(&self, len: u64) -> Result<usize, DecodeError> {
+        if len > self.max_frame_length as u64 {
+            return Err(DecodeError::FrameTooLarge {
+                len,
+                max: self.max_frame_length,
+            });
+        }
+        Ok(len as usize)
+    }
+
+    /// Decodes the length prefix, if it is complete.
+    fn decode_length(&self, src: &mut BytesMut) -> io::Result<Option<usize>> {
+        match u64::try_decode_var(src) {
+            Ok((len, size)) => {
+                let len = self.check_frame_length(len)?;
+                src.advance(size);
+                Ok(Some(len))
+            }
+            Err(DecodeError::UnexpectedEnd) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl Default for VarIntLengthCodec {
+    fn default() -> VarIntLengthCodec {
+        VarIntLengthCodec::new()
+    }
+}
+
+impl Decoder for VarIntLengthCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        // The prefix is decoded and removed from `src` once per frame; while the rest of the
+        // frame arrives, polls only compare the buffered length against the cached one.
+        if self.frame_length.is_none() {
+            self.frame_length = self.decode_length(src)?;
+        }
+        let len = match self.frame_length {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+
+        if src.len() < len {
+            src.reserve(len - src.len());
+            return Ok(None);
+        }
+
+        self.frame_length = None;
+        Ok(Some(src.split_to(len)))
+    }
+}
+
+impl<T: AsRef<[u8]>> Encoder<T> for VarIntLengthCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> io::Result<()> {
+        let data = item.as_ref();
+        self.check_frame_length(data.len() as u64)?;
+
+        let mut prefix = [0_u8; MAX_VARINT_SIZE];
+        let used = data.len().encode_var(&mut prefix);
+        dst.reserve(used + data.len());
+        dst.put_slice(&prefix[..used]);
+        dst.put_slice(data);
+        Ok(())
+    }
+}
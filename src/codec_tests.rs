@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use crate::codec::{VarIntLengthCodec, DEFAULT_MAX_FRAME_LENGTH};
+    use crate::error::DecodeError;
+    use crate::writer::VarIntWriter;
+
+    #[test]
+    fn test_encode_decode() {
+        let mut codec = VarIntLengthCodec::new();
+        assert_eq!(codec.max_frame_length(), DEFAULT_MAX_FRAME_LENGTH);
+
+        let mut buf = BytesMut::new();
+        codec.encode(b"hello", &mut buf).unwrap();
+        codec.encode(vec![7_u8; 200], &mut buf).unwrap();
+        codec.encode(b"", &mut buf).unwrap();
+
+        let mut expected = Vec::new();
+        expected.write_length_delimited(b"hello").unwrap();
+        expected.write_length_delimited(&[7; 200]).unwrap();
+        expected.write_length_delimited(b"").unwrap();
+        assert_eq!(&buf[..], expected.as_slice());
+
+        assert_eq!(&codec.decode(&mut buf).unwrap().unwrap()[..], b"hello");
+        assert_eq!(&codec.decode(&mut buf).unwrap().unwrap()[..], &[7; 200][..]);
+        assert_eq!(&codec.decode(&mut buf).unwrap().unwrap()[..], b"");
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn test_partial_frames() {
+        let mut encoded = Vec::new();
+        encoded.write_length_delimited(&[1; 300]).unwrap();
+        encoded.write_length_delimited(b"abc").unwrap();
+
+        // Feed the frames byte by byte, as if they arrived in many small reads.
+        let mut codec = VarIntLengthCodec::new();
+        let mut buf = BytesMut::new();
+        let mut frames = Vec::new();
+        for b in encoded {
+            buf.extend_from_slice(&[b]);
+            if let Some(frame) = codec.decode(&mut buf).unwrap() {
+                frames.push(frame);
+            }
+        }
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(&frames[0][..], &[1; 300][..]);
+        assert_eq!(&frames[1][..], b"abc");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_length_decoded_once() {
+        let mut encoded = Vec::new();
+        encoded.write_length_delimited(&[1; 300]).unwrap();
+
+        // The two prefix bytes are consumed by the first poll, and only the payload is buffered
+        // while the frame is incomplete.
+        let mut codec = VarIntLengthCodec::new();
+        let mut buf = BytesMut::from(&encoded[..100]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert_eq!(buf.len(), 98);
+        assert!(buf.capacity() >= 300);
+
+        // Later polls use the cached length: bytes looking like a prefix are payload.
+        buf.extend_from_slice(&[0xFF; 10]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert_eq!(buf.len(), 108);
+
+        buf.extend_from_slice(&[2; 192]);
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(frame.len(), 300);
+        assert_eq!(&frame[98..108], &[0xFF; 10]);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_max_frame_length() {
+        let mut codec = VarIntLengthCodec::with_max_frame_length(10);
+
+        let err = codec
+            .encode(&[0_u8; 11][..], &mut BytesMut::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let mut encoded = Vec::new();
+        encoded.write_length_delimited(&[0; 11]).unwrap();
+        let mut buf = BytesMut::from(&encoded[..1]);
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::FrameTooLarge { len: 11, max: 10 })
+        );
+    }
+
+    #[test]
+    fn test_invalid_prefix() {
+        let mut codec = VarIntLengthCodec::new();
+        let mut buf = BytesMut::from(&[0xFF; 11][..]);
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,110 @@
+//! Encoding of whole structs and enums, field by field.
+//!
+//! [`VarIntEncode`] and [`VarIntDecode`] are usually derived with the `derive` feature; see the
+//! derive macros' documentation for how fields are encoded.
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::error::DecodeError;
+use crate::varint::VarInt;
+
+/// Types that can be encoded as a sequence of [`VarInt`](crate::VarInt)s,
+/// [`FixedInt`](crate::FixedInt)s and other `VarIntEncode` types. Primitive integers implement
+/// it by encoding themselves as varint.
+///
+/// ```
+/// # #[cfg(all(feature = "derive", feature = "alloc"))] {
+/// use integer_encoding::{VarIntDecode, VarIntEncode};
+///
+/// #[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+/// struct Entry {
+///     id: u64,
+///     #[encoding(fixed_be)]
+///     checksum: u32,
+/// }
+///
+/// let entry = Entry { id: 300, checksum: 1 };
+/// let encoded = entry.encode_vec();
+/// assert_eq!(encoded, vec![0xAC, 0x02, 0, 0, 0, 1]);
+/// assert_eq!(Entry::decode(&encoded), Ok((entry, 6)));
+/// # }
+/// ```
+pub trait VarIntEncode {
+    /// Returns the number of bytes `encode()` writes for this value.
+    fn required_space(&self) -> usize;
+    /// Encodes the value into `dst`, which must be at least `required_space()` bytes long.
+    /// Returns the number of bytes written.
+    fn encode(&self, dst: &mut [u8]) -> usize;
+
+    /// Helper: Encode the value and return the encoded form as Vec.
+    #[cfg(feature = "alloc")]
+    fn encode_vec(&self) -> Vec<u8> {
+        let mut v = vec![0; self.required_space()];
+        self.encode(&mut v);
+        v
+    }
+}
+
+/// Types that can be decoded from the encoding written by [`VarIntEncode`].
+pub trait VarIntDecode: Sized {
+    /// Decodes a value from the start of `src`. Returns the value and the number of bytes read.
+    fn decode(src: &[u8]) -> Result<(Self, usize), DecodeError>;
+}
+
+// Integers are encoded as varints, so that derived implementations can treat integer fields like
+// all other fields, whatever the integer type is called (e.g. through an alias).
+macro_rules! impl_encode_varint {
+    ($($t:ty),*) => {
+        $(
+            impl VarIntEncode for $t {
+                fn required_space(&self) -> usize {
+                    VarInt::required_space(*self)
+                }
+
+                fn encode(&self, dst: &mut [u8]) -> usize {
+                    self.encode_var(dst)
+                }
+            }
+
+            impl VarIntDecode for $t {
+                fn decode(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                    Self::try_decode_var(src)
+                }
+            }
+        )*
+    };
+}
+
+impl_encode_varint!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Used by the code generated by the derive macros.
+#[doc(hidden)]
+pub mod __private {
+    use crate::error::DecodeError;
+    use crate::fixed::FixedInt;
+
+    pub fn encode_fixed<T: FixedInt>(v: T, dst: &mut [u8]) -> usize {
+        dst[..T::ENCODED_SIZE].copy_from_slice(v.encode_fixed_light().as_ref());
+        T::ENCODED_SIZE
+    }
+
+    pub fn encode_fixed_be<T: FixedInt>(v: T, dst: &mut [u8]) -> usize {
+        dst[..T::ENCODED_SIZE].copy_from_slice(v.encode_fixed_light_be().as_ref());
+        T::ENCODED_SIZE
+    }
+
+    pub fn decode_fixed<T: FixedInt>(src: &[u8]) -> Result<(T, usize), DecodeError> {
+        let src = src
+            .get(..T::ENCODED_SIZE)
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        Ok((T::try_decode_fixed(src)?, T::ENCODED_SIZE))
+    }
+
+    pub fn decode_fixed_be<T: FixedInt>(src: &[u8]) -> Result<(T, usize), DecodeError> {
+        let src = src
+            .get(..T::ENCODED_SIZE)
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        Ok((T::try_decode_fixed_be(src)?, T::ENCODED_SIZE))
+    }
+}
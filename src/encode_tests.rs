@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+
+    use crate::error::DecodeError;
+    use crate::{VarIntDecode, VarIntEncode};
+
+    #[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+    struct Header {
+        length: u32,
+        #[encoding(fixed)]
+        checksum: u32,
+        #[encoding(fixed_be)]
+        port: u16,
+        delta: i64,
+        #[encoding(varint)]
+        offset: i16,
+    }
+
+    #[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+    struct Pair(u8, #[encoding(fixed)] i8);
+
+    #[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+    struct Empty;
+
+    type Id = u64;
+
+    #[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+    struct Spelled {
+        id: Id,
+        length: core::primitive::u32,
+        delta: ::core::primitive::i16,
+    }
+
+    #[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+    enum Message {
+        Ping,
+        Data(Header, u64),
+        Ack {
+            id: u128,
+            #[encoding(fixed_be)]
+            flags: u8,
+        },
+        Nothing(Empty),
+    }
+
+    #[cfg(feature = "alloc")]
+    #[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+    struct Record {
+        id: usize,
+        pair: Pair,
+        message: Message,
+    }
+
+    #[cfg(feature = "alloc")]
+    #[derive(Debug, PartialEq, VarIntEncode, VarIntDecode)]
+    struct Wrap<T> {
+        a: u32,
+        b: T,
+    }
+
+    fn header() -> Header {
+        Header {
+            length: 300,
+            checksum: 0x01020304,
+            port: 0x0102,
+            delta: -1,
+            offset: -2,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn roundtrip<T: VarIntEncode + VarIntDecode + PartialEq + core::fmt::Debug>(v: T) -> Vec<u8> {
+        let encoded = v.encode_vec();
+        assert_eq!(encoded.len(), v.required_space());
+        assert_eq!(T::decode(&encoded), Ok((v, encoded.len())));
+        encoded
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_struct() {
+        assert_eq!(
+            roundtrip(header()),
+            vec![0xAC, 0x02, 4, 3, 2, 1, 1, 2, 1, 3]
+        );
+        assert_eq!(roundtrip(Pair(200, -1)), vec![0xC8, 0x01, 0xFF]);
+        assert_eq!(roundtrip(Empty), Vec::<u8>::new());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_enum() {
+        assert_eq!(roundtrip(Message::Ping), vec![0]);
+        assert_eq!(
+            roundtrip(Message::Ack {
+                id: u128::MAX,
+                flags: 7
+            })
+            .len(),
+            1 + 19 + 1
+        );
+        assert_eq!(roundtrip(Message::Nothing(Empty)), vec![3]);
+
+        let mut encoded = vec![1];
+        encoded.extend_from_slice(&header().encode_vec());
+        encoded.push(5);
+        assert_eq!(roundtrip(Message::Data(header(), 5)), encoded);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_nested() {
+        let r = Record {
+            id: 1,
+            pair: Pair(2, 3),
+            message: Message::Data(header(), u64::MAX),
+        };
+        let encoded = roundtrip(r);
+
+        // Decoding ignores trailing bytes and reports the consumed length.
+        let mut long = encoded.clone();
+        long.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(Record::decode(&long).unwrap().1, encoded.len());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_generic() {
+        assert_eq!(
+            roundtrip(Wrap {
+                a: 1,
+                b: Pair(2, 3)
+            }),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            roundtrip(Wrap {
+                a: 300,
+                b: Wrap { a: 1, b: Empty }
+            }),
+            vec![0xAC, 0x02, 1]
+        );
+    }
+
+    #[test]
+    fn test_integer_spellings() {
+        let mut buf = [0_u8; 16];
+        let value = Spelled {
+            id: 300,
+            length: 1,
+            delta: -1,
+        };
+        let len = value.encode(&mut buf);
+        assert_eq!(&buf[..len], &[0xAC, 0x02, 1, 1]);
+        assert_eq!(value.required_space(), len);
+        assert_eq!(Spelled::decode(&buf[..len]), Ok((value, len)));
+    }
+
+    #[test]
+    fn test_encode_into_slice() {
+        let mut buf = [0xFF_u8; 16];
+        let len = Pair(1, 2).encode(&mut buf);
+        assert_eq!(&buf[..len + 1], &[1, 2, 0xFF]);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut encoded = [0_u8; 16];
+        let len = header().encode(&mut encoded);
+        for i in 0..len {
+            assert_eq!(
+                Header::decode(&encoded[..i]),
+                Err(DecodeError::UnexpectedEnd)
+            );
+        }
+        assert_eq!(
+            Message::decode(&[4]),
+            Err(DecodeError::UnknownVariant { index: 4 })
+        );
+        assert_eq!(Pair::decode(&[0x80, 0x02, 0]), Err(DecodeError::OutOfRange));
+    }
+}
@@ -0,0 +1,120 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Reasons why decoding can fail: decoding single integers in any of the supported encodings
+/// ([`VarInt`](crate::VarInt), [`FixedInt`](crate::FixedInt), [`PrefixVarInt`](crate::PrefixVarInt)
+/// etc.), as well as decoding framing (length-delimited frames and packed fields), the protobuf
+/// wire format and derived [`VarIntDecode`](crate::VarIntDecode) implementations.
+///
+/// The readers ([`VarIntReader`](crate::VarIntReader) etc.) return these wrapped in an
+/// `io::Error`; use `io::Error::get_ref()` and `downcast_ref::<DecodeError>()` to inspect them.
+///
+/// More variants may be added in the future, so matches need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The input ended before a complete integer could be decoded. Returned by all decoding
+    /// functions; the readers report it as `io::ErrorKind::UnexpectedEof`.
+    UnexpectedEnd,
+    /// The varint is longer than any encoding of the target type could be, or its last byte
+    /// carries more bits than fit into the target type. Returned by
+    /// [`VarInt::try_decode_var()`](crate::VarInt::try_decode_var),
+    /// [`Leb128::try_decode_leb128()`](crate::Leb128::try_decode_leb128) and
+    /// [`VarIntProcessor`](crate::VarIntProcessor).
+    Overflow,
+    /// The integer was decoded, but its value is out of range for the requested type (e.g. 300
+    /// decoded as `u8`). Returned by `VarInt::try_decode_var()`,
+    /// [`PrefixVarInt::try_decode_prefix_var()`](crate::PrefixVarInt::try_decode_prefix_var),
+    /// `Leb128::try_decode_leb128()` and the decoding functions of
+    /// [`OrderedInt`](crate::OrderedInt).
+    OutOfRange,
+    /// The integer is not minimally encoded, i.e. it is longer than the shortest encoding of the
+    /// decoded value. Returned by the canonical decoding functions, e.g.
+    /// [`VarInt::try_decode_var_canonical()`](crate::VarInt::try_decode_var_canonical), and by
+    /// [`OrderedInt::try_decode_ordered_var()`](crate::OrderedInt::try_decode_ordered_var).
+    NonCanonical,
+    /// The length prefix of a length-delimited frame exceeds the maximum frame size. Returned by
+    /// [`VarIntReader::read_length_delimited()`](crate::VarIntReader::read_length_delimited),
+    /// the readers of packed fields and `VarIntLengthCodec`.
+    FrameTooLarge { len: u64, max: usize },
+    /// The input slice is longer than the fixed-size encoding of the requested type. Returned by
+    /// [`FixedInt::try_decode_fixed()`](crate::FixedInt::try_decode_fixed) and the `serde`
+    /// helpers, which expect exactly one encoded integer.
+    LengthMismatch { expected: usize, actual: usize },
+    /// The payload of a packed field of [`FixedInt`](crate::FixedInt)s is not a multiple of
+    /// their encoded size. Returned by [`decode_packed_fixed()`](crate::decode_packed_fixed),
+    /// [`packed_fixed_iter()`](crate::packed_fixed_iter) and
+    /// [`FixedIntReader::read_packed_fixed()`](crate::FixedIntReader::read_packed_fixed).
+    PackedLength { len: usize, element_size: usize },
+    /// An enum was encoded with a variant index that doesn't belong to any of its variants.
+    /// Returned by derived [`VarIntDecode::decode()`](crate::VarIntDecode::decode)
+    /// implementations.
+    UnknownVariant { index: u32 },
+    /// A protobuf field key contains a wire type other than those defined by
+    /// [`WireType`](crate::WireType). Returned by [`decode_key()`](crate::decode_key),
+    /// [`skip_field()`](crate::skip_field) and the corresponding reader methods.
+    InvalidWireType { wire_type: u8 },
+    /// A protobuf field key contains field number 0, which is not a valid field number.
+    /// Returned by `decode_key()`, `skip_field()` and the corresponding reader methods.
+    InvalidFieldNumber,
+    /// The end key of a protobuf group has a different field number than its start key.
+    /// Returned by `skip_field()` and
+    /// [`VarIntReader::skip_field()`](crate::VarIntReader::skip_field).
+    GroupMismatch { expected: u32, actual: u32 },
+    /// Protobuf groups are nested deeper than the maximum depth. Returned by `skip_field()` and
+    /// `VarIntReader::skip_field()`.
+    GroupTooDeep { max: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            DecodeError::Overflow => write!(f, "varint overflows target type"),
+            DecodeError::OutOfRange => write!(f, "value out of range for target type"),
+            DecodeError::NonCanonical => write!(f, "varint is not minimally encoded"),
+            DecodeError::FrameTooLarge { len, max } => {
+                write!(f, "frame of {} bytes exceeds maximum of {}", len, max)
+            }
+            DecodeError::LengthMismatch { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
+            DecodeError::PackedLength { len, element_size } => write!(
+                f,
+                "packed field of {} bytes is not a multiple of {} bytes",
+                len, element_size
+            ),
+            DecodeError::UnknownVariant { index } => write!(f, "unknown enum variant {}", index),
+            DecodeError::InvalidWireType { wire_type } => {
+                write!(f, "invalid wire type {}", wire_type)
+            }
+            DecodeError::InvalidFieldNumber => write!(f, "invalid field number 0"),
+            DecodeError::GroupMismatch { expected, actual } => write!(
+                f,
+                "group of field {} ended by key of field {}",
+                expected, actual
+            ),
+            DecodeError::GroupTooDeep { max } => {
+                write!(f, "groups nested deeper than {}", max)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+#[cfg(feature = "std")]
+impl From<DecodeError> for io::Error {
+    /// [`DecodeError::UnexpectedEnd`] maps to [`io::ErrorKind::UnexpectedEof`], everything else
+    /// to [`io::ErrorKind::InvalidData`]. The `DecodeError` is kept as the error's payload; use
+    /// `io::Error::get_ref()` or `into_inner()` and downcast it to get it back.
+    fn from(e: DecodeError) -> io::Error {
+        let kind = match e {
+            DecodeError::UnexpectedEnd => io::ErrorKind::UnexpectedEof,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, e)
+    }
+}
@@ -1,11 +1,18 @@
-use std::convert::TryInto;
-use std::mem::size_of;
+use core::cmp::Ordering;
+use core::convert::TryInto;
+use core::mem::size_of;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::error::DecodeError;
 
 /// `FixedInt` provides encoding/decoding to and from fixed int representations. Note that current
 /// Rust versions already provide this functionality via the `to_le_bytes()` and `to_be_bytes()`
 /// methods.
 ///
-/// The emitted bytestring contains the bytes of the integer in machine endianness.
+/// The emitted bytestring contains the bytes of the integer in little-endian order; the `_be`
+/// variants of the methods below use big-endian (network) order instead.
 pub trait FixedInt: Sized + Copy {
     type Bytes: AsRef<[u8]>;
     const ENCODED_SIZE: usize = size_of::<Self>();
@@ -21,9 +28,27 @@ pub trait FixedInt: Sized + Copy {
 
     /// Decode a value from the given slice assuming little-endian. Use `switch_endianness()` on
     /// the returned value if the source was not encoded in little-endian.
-    fn decode_fixed(src: &[u8]) -> Option<Self>;
+    fn decode_fixed(src: &[u8]) -> Option<Self> {
+        Self::try_decode_fixed(src).ok()
+    }
+    /// Like `decode_fixed()`, but returns a [`DecodeError`] if `src` is too short or too long.
+    fn try_decode_fixed(src: &[u8]) -> Result<Self, DecodeError>;
+
+    /// Encode a value into the given slice using big-endian. Returns `None` if `dst` doesn't
+    /// provide enough space to encode this integer.
+    fn encode_fixed_be(self, dst: &mut [u8]) -> Option<()>;
+    /// Returns the big-endian representation of self in the stack.
+    fn encode_fixed_light_be(self) -> Self::Bytes;
+
+    /// Decode a value from the given slice assuming big-endian.
+    fn decode_fixed_be(src: &[u8]) -> Option<Self> {
+        Self::try_decode_fixed_be(src).ok()
+    }
+    /// Like `decode_fixed_be()`, but returns a [`DecodeError`] if `src` is too short or too long.
+    fn try_decode_fixed_be(src: &[u8]) -> Result<Self, DecodeError>;
 
     /// Helper: Encode the value and return a Vec.
+    #[cfg(feature = "alloc")]
     fn encode_fixed_vec(self) -> Vec<u8> {
         self.encode_fixed_light().as_ref().to_vec()
     }
@@ -31,9 +56,24 @@ pub trait FixedInt: Sized + Copy {
     /// integer-encoding-rs always emits and receives little-endian integers (converting implicitly
     /// on big-endian machines). If you receive a big-endian integer, and would like it to be
     /// treated correctly, use this helper method to convert between endiannesses.
+    ///
+    /// Usually, `encode_fixed_be()` and `decode_fixed_be()` are the better choice.
     fn switch_endianness(self) -> Self;
 }
 
+/// Checks that `src` has exactly the size of a `FI`.
+#[inline]
+fn check_fixed_len<FI: FixedInt>(src: &[u8]) -> Result<(), DecodeError> {
+    match src.len().cmp(&FI::ENCODED_SIZE) {
+        Ordering::Equal => Ok(()),
+        Ordering::Less => Err(DecodeError::UnexpectedEnd),
+        Ordering::Greater => Err(DecodeError::LengthMismatch {
+            expected: FI::ENCODED_SIZE,
+            actual: src.len(),
+        }),
+    }
+}
+
 macro_rules! impl_fixedint {
     ($t:ty) => {
         impl FixedInt for $t {
@@ -52,14 +92,29 @@ macro_rules! impl_fixedint {
                 self.to_le_bytes()
             }
 
-            fn decode_fixed(src: &[u8]) -> Option<Self> {
-                if src.len() == size_of::<Self>() {
-                    Some(Self::from_le_bytes(src.try_into().unwrap()))
+            fn try_decode_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+                check_fixed_len::<Self>(src)?;
+                Ok(Self::from_le_bytes(src.try_into().unwrap()))
+            }
+
+            fn encode_fixed_be(self, dst: &mut [u8]) -> Option<()> {
+                if dst.len() == size_of::<Self>() {
+                    dst.clone_from_slice(&self.to_be_bytes());
+                    Some(())
                 } else {
                     None
                 }
             }
 
+            fn encode_fixed_light_be(self) -> Self::Bytes {
+                self.to_be_bytes()
+            }
+
+            fn try_decode_fixed_be(src: &[u8]) -> Result<Self, DecodeError> {
+                check_fixed_len::<Self>(src)?;
+                Ok(Self::from_be_bytes(src.try_into().unwrap()))
+            }
+
             fn switch_endianness(self) -> Self {
                 Self::from_le_bytes(self.to_be_bytes())
             }
@@ -68,11 +123,13 @@ macro_rules! impl_fixedint {
 }
 
 impl_fixedint!(usize);
+impl_fixedint!(u128);
 impl_fixedint!(u64);
 impl_fixedint!(u32);
 impl_fixedint!(u16);
 impl_fixedint!(u8);
 impl_fixedint!(isize);
+impl_fixedint!(i128);
 impl_fixedint!(i64);
 impl_fixedint!(i32);
 impl_fixedint!(i16);
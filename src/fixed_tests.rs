@@ -1,5 +1,9 @@
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+
+    use crate::error::DecodeError;
     use crate::fixed::FixedInt;
 
     #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
@@ -7,14 +11,18 @@ mod tests {
     #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
     use crate::writer::FixedIntAsyncWriter;
 
+    #[cfg(feature = "std")]
     use crate::reader::FixedIntReader;
+    #[cfg(feature = "std")]
     use crate::writer::FixedIntWriter;
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_u32_enc() {
         let result = 32_u32.encode_fixed_vec();
         assert_eq!(result, vec![32, 0, 0, 0]);
     }
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_u16_enc() {
         let result = 256_u16.encode_fixed_vec();
@@ -44,21 +52,25 @@ mod tests {
         let be = le.switch_endianness();
         assert_eq!(be, -257);
     }
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_u8_enc() {
         let result = 255_u8.encode_fixed_vec();
         assert_eq!(result, vec![255]);
     }
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_i8_enc() {
         let result = (-1_i8).encode_fixed_vec();
         assert_eq!(result, vec![255]);
     }
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_i16_enc() {
         let result = (-32768_i16).encode_fixed_vec();
         assert_eq!(result, vec![0, 128]);
     }
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_i32_enc() {
         let result = (-32767_i32).encode_fixed_vec();
@@ -81,6 +93,13 @@ mod tests {
         let result = int.encode_fixed_light();
         assert_eq!(result, [1, 128, 255, 255]);
     }
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_u128_enc() {
+        let result = (1_u128 << 64).encode_fixed_vec();
+        assert_eq!(result, vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(1_u128.switch_endianness(), 1 << 120);
+    }
     #[test]
     fn test_all_identity() {
         let a: u8 = 17;
@@ -91,6 +110,8 @@ mod tests {
         let f: i16 = -17;
         let g: i32 = -17;
         let h: i64 = -17;
+        let i: u128 = 17;
+        let j: i128 = -17;
 
         assert_eq!(a, FixedInt::decode_fixed(&a.encode_fixed_light()).unwrap());
         assert_eq!(b, FixedInt::decode_fixed(&b.encode_fixed_light()).unwrap());
@@ -100,8 +121,11 @@ mod tests {
         assert_eq!(f, FixedInt::decode_fixed(&f.encode_fixed_light()).unwrap());
         assert_eq!(g, FixedInt::decode_fixed(&g.encode_fixed_light()).unwrap());
         assert_eq!(h, FixedInt::decode_fixed(&h.encode_fixed_light()).unwrap());
+        assert_eq!(i, FixedInt::decode_fixed(&i.encode_fixed_light()).unwrap());
+        assert_eq!(j, FixedInt::decode_fixed(&j.encode_fixed_light()).unwrap());
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_reader_writer() {
         let mut buf = Vec::with_capacity(128);
@@ -109,26 +133,82 @@ mod tests {
         let i1: u32 = 123;
         let i2: u32 = 124;
         let i3: u32 = 125;
+        let i4: i128 = i128::MIN + 126;
 
         assert!(buf.write_fixedint(i1).is_ok());
         assert!(buf.write_fixedint(i2).is_ok());
         assert!(buf.write_fixedint(i3).is_ok());
+        assert!(buf.write_fixedint(i4).is_ok());
 
-        assert_eq!(3 * 4, buf.len());
+        assert_eq!(3 * 4 + 16, buf.len());
 
         let mut reader: &[u8] = buf.as_ref();
 
         let i1_res = reader.read_fixedint().unwrap();
         let i2_res = reader.read_fixedint().unwrap();
         let i3_res = reader.read_fixedint().unwrap();
+        let i4_res = reader.read_fixedint().unwrap();
 
         assert_eq!(i1, i1_res);
         assert_eq!(i2, i2_res);
         assert_eq!(i3, i3_res);
+        assert_eq!(i4, i4_res);
 
         assert!(reader.read_fixedint::<u32>().is_err());
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_writer_be() {
+        let mut buf = Vec::with_capacity(128);
+
+        assert_eq!(buf.write_fixedint_be(0x0102_u16).unwrap(), 2);
+        assert_eq!(buf.write_fixedint_be(-3_i64).unwrap(), 8);
+        assert_eq!(buf[..4], [1, 2, 0xFF, 0xFF]);
+
+        let mut reader: &[u8] = buf.as_ref();
+
+        assert_eq!(0x0102_u16, reader.read_fixedint_be().unwrap());
+        assert_eq!(-3_i64, reader.read_fixedint_be().unwrap());
+        assert!(reader.read_fixedint_be::<u32>().is_err());
+    }
+
+    #[test]
+    fn test_big_endian() {
+        assert_eq!(0x1234_u16.encode_fixed_light_be(), [0x12, 0x34]);
+        assert_eq!((-2_i32).encode_fixed_light_be(), [0xFF, 0xFF, 0xFF, 0xFE]);
+
+        let mut buf = [0_u8; 4];
+        0x01020304_u32.encode_fixed_be(&mut buf).unwrap();
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert_eq!(u32::decode_fixed_be(&buf), Some(0x01020304));
+        assert_eq!(
+            u32::decode_fixed(&buf),
+            Some(0x01020304_u32.switch_endianness())
+        );
+        assert!(0_u64.encode_fixed_be(&mut buf).is_none());
+        assert_eq!(
+            u32::try_decode_fixed_be(&buf[..2]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn test_try_decode_fixed() {
+        assert_eq!(u32::try_decode_fixed(&[1, 0, 0, 0]), Ok(1));
+        assert_eq!(
+            u32::try_decode_fixed(&[1, 0, 0]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            u32::try_decode_fixed(&[1, 0, 0, 0, 0]),
+            Err(DecodeError::LengthMismatch {
+                expected: 4,
+                actual: 5
+            })
+        );
+    }
+
     #[should_panic]
     #[test]
     fn test_invalid_decode_size() {
@@ -153,6 +233,7 @@ mod tests {
         let i5: i32 = -32456;
         let i6: i8 = -128;
         let i7: u8 = 255;
+        let i8: u128 = u128::MAX - 1;
 
         buf.write_fixedint_async(i1).await.unwrap();
         buf.write_fixedint_async(i2).await.unwrap();
@@ -161,6 +242,7 @@ mod tests {
         buf.write_fixedint_async(i5).await.unwrap();
         buf.write_fixedint_async(i6).await.unwrap();
         buf.write_fixedint_async(i7).await.unwrap();
+        buf.write_fixedint_async(i8).await.unwrap();
 
         let mut reader: &[u8] = buf.as_ref();
 
@@ -171,6 +253,23 @@ mod tests {
         assert_eq!(i5, reader.read_fixedint_async().await.unwrap());
         assert_eq!(i6, reader.read_fixedint_async().await.unwrap());
         assert_eq!(i7, reader.read_fixedint_async().await.unwrap());
+        assert_eq!(i8, reader.read_fixedint_async().await.unwrap());
         assert!(reader.read_fixedint_async::<u32>().await.is_err());
     }
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    #[tokio::test]
+    async fn test_async_reader_be() {
+        let mut buf = Vec::with_capacity(128);
+
+        buf.write_fixedint_be_async(0x0102_u16).await.unwrap();
+        buf.write_fixedint_be_async(-3_i64).await.unwrap();
+        assert_eq!(buf[..4], [1, 2, 0xFF, 0xFF]);
+
+        let mut reader: &[u8] = buf.as_ref();
+
+        assert_eq!(0x0102_u16, reader.read_fixedint_be_async().await.unwrap());
+        assert_eq!(-3_i64, reader.read_fixedint_be_async().await.unwrap());
+        assert!(reader.read_fixedint_be_async::<u32>().await.is_err());
+    }
 }
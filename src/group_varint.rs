@@ -0,0 +1,285 @@
+//! Group varint encoding of `u32`s, as described in Jeff Dean's "Challenges in Building
+//! Large-Scale Information Retrieval Systems".
+//!
+//! Values are encoded in groups of four: a tag byte holding the byte lengths (minus one) of the
+//! four values in two bits each, starting at the least significant bits, followed by the values
+//! in little-endian order, using 1 to 4 bytes each. Because the lengths of all four values are
+//! known up front, decoding doesn't need to inspect every byte like [`VarInt`](crate::VarInt)
+//! decoding does.
+//!
+//! If the number of values is not a multiple of four, the last group only contains the remaining
+//! values; the unused bits of its tag are zero. The number of values is not part of the
+//! encoding and must be passed to the decoder.
+
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+use alloc::vec::Vec;
+
+use crate::error::DecodeError;
+
+/// The longest possible encoded group, in bytes.
+const MAX_GROUP_SIZE: usize = 17;
+
+/// Masks for the lowest 1, 2, 3 and 4 bytes of a `u32`.
+const MASKS: [u32; 4] = [0xFF, 0xFFFF, 0xFF_FFFF, 0xFFFF_FFFF];
+
+/// Returns the number of bytes needed to encode `v` within a group.
+#[inline]
+pub(crate) fn byte_len(v: u32) -> usize {
+    1 + (v > 0xFF) as usize + (v > 0xFFFF) as usize + (v > 0xFF_FFFF) as usize
+}
+
+/// Returns the number of bytes needed to encode `src` as group varints.
+pub fn group_varint_required_space(src: &[u32]) -> usize {
+    src.len().div_ceil(4) + src.iter().map(|&v| byte_len(v)).sum::<usize>()
+}
+
+/// Encodes up to four values as one group into `dst`, which must be at least
+/// `MAX_GROUP_SIZE` bytes long: each value is written as four bytes, of which only the needed
+/// ones are kept. Returns the number of bytes written.
+#[inline]
+fn encode_group(values: &[u32], dst: &mut [u8]) -> usize {
+    debug_assert!(!values.is_empty() && values.len() <= 4);
+    let mut tag = 0;
+    let mut i = 1;
+    for (k, &v) in values.iter().enumerate() {
+        let len = byte_len(v);
+        tag |= ((len - 1) as u8) << (2 * k);
+        dst[i..i + 4].copy_from_slice(&v.to_le_bytes());
+        i += len;
+    }
+    dst[0] = tag;
+    i
+}
+
+/// Decodes a group of `n` (at most four) values from `src` into `dst`. Returns the number of
+/// bytes read.
+#[inline]
+fn decode_group(src: &[u8], n: usize, dst: &mut [u32; 4]) -> Result<usize, DecodeError> {
+    debug_assert!(n > 0 && n <= 4);
+    let tag = *src.first().ok_or(DecodeError::UnexpectedEnd)?;
+
+    if n == 4 && src.len() >= MAX_GROUP_SIZE {
+        // Fast path: every value can be read as a full u32 and masked.
+        let mut i = 1;
+        for (k, v) in dst.iter_mut().enumerate() {
+            let len = ((tag >> (2 * k)) & 0b11) as usize;
+            let word = u32::from_le_bytes([src[i], src[i + 1], src[i + 2], src[i + 3]]);
+            *v = word & MASKS[len];
+            i += len + 1;
+        }
+        return Ok(i);
+    }
+
+    let mut i = 1;
+    for (k, v) in dst[..n].iter_mut().enumerate() {
+        let len = ((tag >> (2 * k)) & 0b11) as usize + 1;
+        let bytes = src.get(i..i + len).ok_or(DecodeError::UnexpectedEnd)?;
+        let mut buf = [0_u8; 4];
+        buf[..len].copy_from_slice(bytes);
+        *v = u32::from_le_bytes(buf);
+        i += len;
+    }
+    Ok(i)
+}
+
+/// Encodes all values in `src` as group varints and appends them to `dst`. Returns the number of
+/// bytes appended.
+pub fn encode_group_varint(src: &[u32], dst: &mut Vec<u8>) -> usize {
+    let start = dst.len();
+    // Every value is written as four bytes, so reserve the worst case and truncate afterwards.
+    dst.resize(start + src.len().div_ceil(4) * MAX_GROUP_SIZE, 0);
+
+    let mut i = start;
+    for group in src.chunks(4) {
+        i += encode_group(group, &mut dst[i..]);
+    }
+    dst.truncate(i);
+    i - start
+}
+
+/// Decodes `count` group varints from `src`, appending them to `dst`. Returns the number of bytes
+/// read from `src`; any bytes after the last group are ignored.
+///
+/// If `src` ends before `count` values were decoded, `dst` is restored to its previous length
+/// and [`DecodeError::UnexpectedEnd`] is returned.
+pub fn decode_group_varint(
+    src: &[u8],
+    count: usize,
+    dst: &mut Vec<u32>,
+) -> Result<usize, DecodeError> {
+    let start = dst.len();
+    dst.reserve(count.min(src.len() * 4));
+
+    let mut i = 0;
+    let mut remaining = count;
+    let mut group = [0_u32; 4];
+    while remaining > 0 {
+        let n = remaining.min(4);
+        match decode_group(&src[i..], n, &mut group) {
+            Ok(size) => i += size,
+            Err(e) => {
+                dst.truncate(start);
+                return Err(e);
+            }
+        }
+        dst.extend_from_slice(&group[..n]);
+        remaining -= n;
+    }
+    Ok(i)
+}
+
+/// Writes `u32`s as group varints to a [`Write`], buffering up to four values at a time.
+///
+/// Call `finish()` after the last value to write the final, possibly incomplete, group; values
+/// still buffered when the writer is dropped are lost.
+///
+/// ```
+/// use integer_encoding::{GroupVarIntReader, GroupVarIntWriter};
+///
+/// let mut w = GroupVarIntWriter::new(Vec::new());
+/// for v in [1, 300, 70000, 5, 6] {
+///     w.push(v).unwrap();
+/// }
+/// let encoded = w.finish().unwrap();
+///
+/// let r = GroupVarIntReader::new(&encoded[..], 5);
+/// assert_eq!(r.collect::<Result<Vec<u32>, _>>().unwrap(), vec![1, 300, 70000, 5, 6]);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct GroupVarIntWriter<W> {
+    inner: W,
+    group: [u32; 4],
+    buffered: usize,
+    count: usize,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> GroupVarIntWriter<W> {
+    pub fn new(inner: W) -> GroupVarIntWriter<W> {
+        GroupVarIntWriter {
+            inner,
+            group: [0; 4],
+            buffered: 0,
+            count: 0,
+        }
+    }
+
+    /// Adds a value, writing a group to the underlying writer once four values are buffered.
+    pub fn push(&mut self, v: u32) -> io::Result<()> {
+        self.group[self.buffered] = v;
+        self.buffered += 1;
+        self.count += 1;
+        if self.buffered == 4 {
+            self.flush_group()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of values pushed so far, which must be passed to
+    /// [`GroupVarIntReader::new()`] to read them back.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Writes the buffered values, if any, and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush_group()?;
+        Ok(self.inner)
+    }
+
+    fn flush_group(&mut self) -> io::Result<()> {
+        if self.buffered == 0 {
+            return Ok(());
+        }
+        let mut buf = [0_u8; MAX_GROUP_SIZE];
+        let len = encode_group(&self.group[..self.buffered], &mut buf);
+        self.buffered = 0;
+        self.inner.write_all(&buf[..len])
+    }
+}
+
+/// Reads a known number of group varints from a [`Read`], as written by [`GroupVarIntWriter`] or
+/// [`encode_group_varint()`].
+///
+/// Each item is either a decoded value or the error encountered when reading the next group. The
+/// iterator ends after `count` values, or after the first error. It's recommended to use a
+/// buffered reader, as each group is read separately.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct GroupVarIntReader<R> {
+    inner: R,
+    remaining: usize,
+    group: [u32; 4],
+    buffered: usize,
+    next: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> GroupVarIntReader<R> {
+    /// Creates a reader for `count` values.
+    pub fn new(inner: R, count: usize) -> GroupVarIntReader<R> {
+        GroupVarIntReader {
+            inner,
+            remaining: count,
+            group: [0; 4],
+            buffered: 0,
+            next: 0,
+        }
+    }
+
+    /// Returns the underlying reader. Values of a partially consumed group are discarded.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn read_group(&mut self) -> io::Result<()> {
+        let n = self.remaining.min(4);
+        let mut buf = [0_u8; MAX_GROUP_SIZE];
+        self.inner.read_exact(&mut buf[..1])?;
+        let len: usize = (0..n)
+            .map(|k| ((buf[0] >> (2 * k)) & 0b11) as usize + 1)
+            .sum();
+        self.inner.read_exact(&mut buf[1..1 + len])?;
+        decode_group(&buf[..1 + len], n, &mut self.group)?;
+        self.buffered = n;
+        self.next = 0;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> Iterator for GroupVarIntReader<R> {
+    type Item = io::Result<u32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.buffered {
+            if self.remaining == 0 {
+                return None;
+            }
+            if let Err(e) = self.read_group() {
+                self.remaining = 0;
+                let e = if e.kind() == io::ErrorKind::UnexpectedEof {
+                    io::Error::from(DecodeError::UnexpectedEnd)
+                } else {
+                    e
+                };
+                return Some(Err(e));
+            }
+            self.remaining -= self.buffered;
+        }
+        let v = self.group[self.next];
+        self.next += 1;
+        Some(Ok(v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining + self.buffered - self.next;
+        (len, Some(len))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> core::iter::FusedIterator for GroupVarIntReader<R> {}
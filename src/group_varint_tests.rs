@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    #[cfg(feature = "std")]
+    use std::io;
+
+    use crate::error::DecodeError;
+    use crate::group_varint::{
+        decode_group_varint, encode_group_varint, group_varint_required_space,
+    };
+    #[cfg(feature = "std")]
+    use crate::group_varint::{GroupVarIntReader, GroupVarIntWriter};
+
+    #[test]
+    fn test_encode() {
+        let mut buf = vec![];
+        assert_eq!(
+            encode_group_varint(&[1, 300, 70000, 0x1234_5678], &mut buf),
+            11
+        );
+        assert_eq!(
+            buf,
+            vec![
+                0b11_10_01_00,
+                1,
+                0x2C,
+                0x01,
+                0x70,
+                0x11,
+                0x01,
+                0x78,
+                0x56,
+                0x34,
+                0x12
+            ]
+        );
+
+        // The last group only holds the remaining values.
+        buf.clear();
+        assert_eq!(encode_group_varint(&[0, 0, 0, 0, 256], &mut buf), 8);
+        assert_eq!(buf, vec![0, 0, 0, 0, 0, 0b01, 0x00, 0x01]);
+
+        buf.clear();
+        assert_eq!(encode_group_varint(&[], &mut buf), 0);
+        assert!(buf.is_empty());
+    }
+
+    fn sample(len: usize) -> Vec<u32> {
+        (0..len as u32)
+            .map(|i| i.wrapping_mul(0x9E37_79B9) >> (i % 32))
+            .collect()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for len in 0..=9 {
+            for &values in &[&sample(len)[..], &[u32::MAX; 9][..len], &[0; 9][..len]] {
+                // Appends to existing contents.
+                let mut encoded = vec![0xAA];
+                let size = encode_group_varint(values, &mut encoded);
+                assert_eq!(size, group_varint_required_space(values));
+                assert_eq!(encoded.len(), size + 1);
+
+                let mut decoded = vec![7];
+                assert_eq!(
+                    decode_group_varint(&encoded[1..], values.len(), &mut decoded),
+                    Ok(size)
+                );
+                assert_eq!(decoded[0], 7);
+                assert_eq!(&decoded[1..], values);
+            }
+        }
+
+        // Long inputs take the fast path for all but the last groups.
+        let values = sample(1000);
+        let mut encoded = vec![];
+        let size = encode_group_varint(&values, &mut encoded);
+        let mut decoded = vec![];
+        assert_eq!(decode_group_varint(&encoded, 1000, &mut decoded), Ok(size));
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn test_decode_trailing_bytes() {
+        let mut encoded = vec![];
+        encode_group_varint(&[1, 2, 3], &mut encoded);
+        encoded.extend_from_slice(&[0xFF; 20]);
+
+        let mut decoded = vec![];
+        assert_eq!(decode_group_varint(&encoded, 3, &mut decoded), Ok(4));
+        assert_eq!(decoded, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        let values = sample(10);
+        let mut encoded = vec![];
+        encode_group_varint(&values, &mut encoded);
+
+        for end in 0..encoded.len() {
+            let mut decoded = vec![1, 2];
+            assert_eq!(
+                decode_group_varint(&encoded[..end], 10, &mut decoded),
+                Err(DecodeError::UnexpectedEnd)
+            );
+            assert_eq!(decoded, vec![1, 2]);
+        }
+
+        let mut decoded = vec![];
+        assert_eq!(
+            decode_group_varint(&encoded, 11, &mut decoded),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert!(decoded.is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_writer() {
+        let values = sample(23);
+        let mut w = GroupVarIntWriter::new(vec![]);
+        for &v in &values {
+            w.push(v).unwrap();
+        }
+        assert_eq!(w.count(), 23);
+        let encoded = w.finish().unwrap();
+
+        let mut expected = vec![];
+        encode_group_varint(&values, &mut expected);
+        assert_eq!(encoded, expected);
+
+        let mut r = GroupVarIntReader::new(&encoded[..], 23);
+        assert_eq!(r.size_hint(), (23, Some(23)));
+        assert_eq!(r.next().unwrap().unwrap(), values[0]);
+        assert_eq!(r.size_hint(), (22, Some(22)));
+        let rest: Vec<u32> = r.by_ref().collect::<io::Result<_>>().unwrap();
+        assert_eq!(rest, &values[1..]);
+        assert!(r.next().is_none());
+        assert!(r.into_inner().is_empty());
+
+        let w = GroupVarIntWriter::new(vec![]);
+        assert!(w.finish().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_eof() {
+        let mut encoded = vec![];
+        encode_group_varint(&[1, 2, 3, 4, 5], &mut encoded);
+
+        let mut r = GroupVarIntReader::new(&encoded[..encoded.len() - 1], 5);
+        for v in 1..=4 {
+            assert_eq!(r.next().unwrap().unwrap(), v);
+        }
+        let e = r.next().unwrap().unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(
+            e.into_inner().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::UnexpectedEnd)
+        );
+        // The reader is fused after an error.
+        assert!(r.next().is_none());
+        assert_eq!(r.size_hint(), (0, Some(0)));
+    }
+}
@@ -0,0 +1,64 @@
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+use crate::error::DecodeError;
+use crate::varint::VarInt;
+
+/// An iterator over consecutive [`VarInt`]s in a byte slice, created by
+/// [`VarInt::iter_var()`].
+///
+/// Each item is either a decoded value or the [`DecodeError`] encountered when decoding the next
+/// varint. The iterator ends once the slice is exhausted, or after the first error; in that case,
+/// `offset()` and `remaining()` point to the start of the invalid varint.
+#[derive(Debug, Clone)]
+pub struct VarIntIter<'a, VI> {
+    src: &'a [u8],
+    offset: usize,
+    failed: bool,
+    _marker: PhantomData<VI>,
+}
+
+impl<'a, VI: VarInt> VarIntIter<'a, VI> {
+    /// Creates an iterator over the varints in `src`.
+    pub fn new(src: &'a [u8]) -> VarIntIter<'a, VI> {
+        VarIntIter {
+            src,
+            offset: 0,
+            failed: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the offset of the next varint in the original slice.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the bytes not yet consumed by the iterator.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.src[self.offset..]
+    }
+}
+
+impl<'a, VI: VarInt> Iterator for VarIntIter<'a, VI> {
+    type Item = Result<VI, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.offset >= self.src.len() {
+            return None;
+        }
+
+        match VI::try_decode_var(&self.src[self.offset..]) {
+            Ok((v, size)) => {
+                self.offset += size;
+                Some(Ok(v))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a, VI: VarInt> FusedIterator for VarIntIter<'a, VI> {}
@@ -0,0 +1,206 @@
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::error::DecodeError;
+use crate::varint::MSB;
+
+/// The sign bit of the last byte of a signed LEB128 value.
+const SIGN_BIT: u8 = 0b0100_0000;
+
+/// LEB128 encoding, as used by DWARF and WebAssembly.
+///
+/// Unsigned integers are encoded like [`VarInt`](crate::VarInt)s. Signed integers, however, are
+/// encoded as their two's complement, sign-extended from the last byte, instead of using zigzag
+/// encoding: -1 is encoded as `[0x7F]`, 64 as `[0xC0, 0x00]`.
+///
+/// Decoding follows the rules of the WebAssembly specification: an encoded value may be padded
+/// with redundant bytes, as long as it takes at most [`MAX_ENCODED_SIZE`](Self::MAX_ENCODED_SIZE)
+/// bytes, and the unused bits of the last byte of a maximum length encoding must be zero
+/// (unsigned), or equal to the sign bit (signed).
+///
+/// ```
+/// use integer_encoding::Leb128;
+///
+/// let mut buf = [0_u8; 5];
+/// assert_eq!((-123456_i32).encode_leb128(&mut buf), 3);
+/// assert_eq!(&buf[..3], &[0xC0, 0xBB, 0x78]);
+/// assert_eq!(i32::decode_leb128(&buf), Some((-123456, 3)));
+///
+/// // Padded to the maximum length, e.g. for patching in a size later.
+/// assert_eq!(3_u32.encode_leb128_padded(&mut buf, 5), 5);
+/// assert_eq!(buf, [0x83, 0x80, 0x80, 0x80, 0x00]);
+/// assert_eq!(u32::decode_leb128(&buf), Some((3, 5)));
+/// ```
+pub trait Leb128: Sized + Copy {
+    /// The largest number of bytes an encoded value of this type may take.
+    const MAX_ENCODED_SIZE: usize;
+
+    /// Returns the number of bytes the shortest encoding of this value takes.
+    fn leb128_required_space(self) -> usize;
+
+    /// Decode a value from the slice. Returns the value and the number of bytes read, or `None`
+    /// if `src` doesn't start with a valid LEB128 value of this type.
+    ///
+    /// Use `try_decode_leb128()` to find out why decoding failed.
+    fn decode_leb128(src: &[u8]) -> Option<(Self, usize)> {
+        Self::try_decode_leb128(src).ok()
+    }
+    /// Like `decode_leb128()`, but returns a [`DecodeError`] describing why a value could not be
+    /// decoded: [`DecodeError::UnexpectedEnd`] if `src` ends before the value does,
+    /// [`DecodeError::Overflow`] if the encoding is longer than `MAX_ENCODED_SIZE` bytes, and
+    /// [`DecodeError::OutOfRange`] if the value does not fit into this type.
+    fn try_decode_leb128(src: &[u8]) -> Result<(Self, usize), DecodeError>;
+
+    /// Encode the shortest encoding of a value into the slice, which must be at least
+    /// `leb128_required_space()` bytes long. Returns the number of bytes written.
+    fn encode_leb128(self, dst: &mut [u8]) -> usize;
+    /// Encode a value into exactly `len` bytes of the slice, padding it with redundant bytes.
+    /// Returns `len`.
+    ///
+    /// Panics if `len` is smaller than `leb128_required_space()` or larger than
+    /// `MAX_ENCODED_SIZE`, or if `dst` is shorter than `len`.
+    fn encode_leb128_padded(self, dst: &mut [u8], len: usize) -> usize;
+
+    /// Helper: Encode a value and return the encoded form as Vec.
+    #[cfg(feature = "alloc")]
+    fn encode_leb128_vec(self) -> Vec<u8> {
+        let mut v = vec![0; self.leb128_required_space()];
+        self.encode_leb128(&mut v);
+        v
+    }
+}
+
+macro_rules! impl_leb128 {
+    ($t:ty, unsigned) => {
+        impl Leb128 for $t {
+            const MAX_ENCODED_SIZE: usize = (<$t>::BITS as usize).div_ceil(7);
+
+            fn leb128_required_space(self) -> usize {
+                let bits = (<$t>::BITS - self.leading_zeros()) as usize;
+                bits.div_ceil(7).max(1)
+            }
+
+            fn try_decode_leb128(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let mut result: $t = 0;
+                let mut shift = 0;
+
+                for (i, &b) in src.iter().enumerate() {
+                    let payload = b & !MSB;
+                    if i == Self::MAX_ENCODED_SIZE - 1 {
+                        if b & MSB != 0 {
+                            return Err(DecodeError::Overflow);
+                        }
+                        // The bits beyond the type's width must be zero.
+                        let used = <$t>::BITS - shift;
+                        if used < 7 && payload >> used != 0 {
+                            return Err(DecodeError::OutOfRange);
+                        }
+                    }
+                    result |= <$t>::from(payload) << shift;
+                    if b & MSB == 0 {
+                        return Ok((result, i + 1));
+                    }
+                    shift += 7;
+                }
+
+                Err(DecodeError::UnexpectedEnd)
+            }
+
+            fn encode_leb128(self, dst: &mut [u8]) -> usize {
+                self.encode_leb128_padded(dst, self.leb128_required_space())
+            }
+
+            fn encode_leb128_padded(self, dst: &mut [u8], len: usize) -> usize {
+                assert!(len >= self.leb128_required_space() && len <= Self::MAX_ENCODED_SIZE);
+                let mut n = self;
+                for (i, b) in dst[..len].iter_mut().enumerate() {
+                    *b = n as u8 & !MSB;
+                    if i + 1 < len {
+                        *b |= MSB;
+                    }
+                    n >>= 7;
+                }
+                len
+            }
+        }
+    };
+    ($t:ty, signed) => {
+        impl Leb128 for $t {
+            const MAX_ENCODED_SIZE: usize = (<$t>::BITS as usize).div_ceil(7);
+
+            fn leb128_required_space(self) -> usize {
+                let redundant = if self < 0 {
+                    self.leading_ones()
+                } else {
+                    self.leading_zeros()
+                };
+                // The significant bits, plus one for the sign.
+                let bits = (<$t>::BITS - redundant + 1) as usize;
+                bits.div_ceil(7)
+            }
+
+            fn try_decode_leb128(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let mut result: $t = 0;
+                let mut shift = 0;
+
+                for (i, &b) in src.iter().enumerate() {
+                    let payload = b & !MSB;
+                    if i == Self::MAX_ENCODED_SIZE - 1 {
+                        if b & MSB != 0 {
+                            return Err(DecodeError::Overflow);
+                        }
+                        // The bits beyond the type's width must equal the sign bit.
+                        let used = <$t>::BITS - shift;
+                        let extension = (!MSB << (used - 1)) & !MSB;
+                        if payload & extension != 0 && payload & extension != extension {
+                            return Err(DecodeError::OutOfRange);
+                        }
+                    }
+                    // Bits shifted beyond the type's width are dropped.
+                    result |= (payload as $t) << shift;
+                    shift += 7;
+                    if b & MSB == 0 {
+                        if shift < <$t>::BITS && b & SIGN_BIT != 0 {
+                            result |= -1 << shift;
+                        }
+                        return Ok((result, i + 1));
+                    }
+                }
+
+                Err(DecodeError::UnexpectedEnd)
+            }
+
+            fn encode_leb128(self, dst: &mut [u8]) -> usize {
+                self.encode_leb128_padded(dst, self.leb128_required_space())
+            }
+
+            fn encode_leb128_padded(self, dst: &mut [u8], len: usize) -> usize {
+                assert!(len >= self.leb128_required_space() && len <= Self::MAX_ENCODED_SIZE);
+                let mut n = self;
+                for (i, b) in dst[..len].iter_mut().enumerate() {
+                    *b = n as u8 & !MSB;
+                    if i + 1 < len {
+                        *b |= MSB;
+                    }
+                    // Arithmetic shift: padding bytes carry the sign.
+                    n >>= 7;
+                }
+                len
+            }
+        }
+    };
+}
+
+impl_leb128!(u8, unsigned);
+impl_leb128!(u16, unsigned);
+impl_leb128!(u32, unsigned);
+impl_leb128!(u64, unsigned);
+impl_leb128!(u128, unsigned);
+impl_leb128!(usize, unsigned);
+
+impl_leb128!(i8, signed);
+impl_leb128!(i16, signed);
+impl_leb128!(i32, signed);
+impl_leb128!(i64, signed);
+impl_leb128!(i128, signed);
+impl_leb128!(isize, signed);
@@ -0,0 +1,228 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    #[cfg(feature = "std")]
+    use std::io;
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::reader::Leb128AsyncReader;
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::writer::Leb128AsyncWriter;
+
+    use crate::error::DecodeError;
+    use crate::leb128::Leb128;
+    #[cfg(feature = "std")]
+    use crate::reader::Leb128Reader;
+    #[cfg(feature = "alloc")]
+    use crate::varint::VarInt;
+    #[cfg(feature = "std")]
+    use crate::writer::Leb128Writer;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_unsigned() {
+        // Examples from the DWARF specification.
+        assert_eq!(2_u32.encode_leb128_vec(), vec![2]);
+        assert_eq!(127_u32.encode_leb128_vec(), vec![0x7F]);
+        assert_eq!(128_u32.encode_leb128_vec(), vec![0x80, 0x01]);
+        assert_eq!(129_u32.encode_leb128_vec(), vec![0x81, 0x01]);
+        assert_eq!(130_u32.encode_leb128_vec(), vec![0x82, 0x01]);
+        assert_eq!(12857_u32.encode_leb128_vec(), vec![0xB9, 0x64]);
+
+        assert_eq!(0_u8.encode_leb128_vec(), vec![0]);
+        assert_eq!(u8::MAX.encode_leb128_vec(), vec![0xFF, 0x01]);
+        assert_eq!(
+            u32::MAX.encode_leb128_vec(),
+            vec![0xFF, 0xFF, 0xFF, 0xFF, 0x0F]
+        );
+        assert_eq!(u64::MAX.encode_leb128_vec(), u64::MAX.encode_var_vec());
+        assert_eq!(u128::MAX.encode_leb128_vec(), u128::MAX.encode_var_vec());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_signed() {
+        // Examples from the DWARF specification.
+        assert_eq!(2_i32.encode_leb128_vec(), vec![2]);
+        assert_eq!((-2_i32).encode_leb128_vec(), vec![0x7E]);
+        assert_eq!(127_i32.encode_leb128_vec(), vec![0xFF, 0x00]);
+        assert_eq!((-127_i32).encode_leb128_vec(), vec![0x81, 0x7F]);
+        assert_eq!(128_i32.encode_leb128_vec(), vec![0x80, 0x01]);
+        assert_eq!((-128_i32).encode_leb128_vec(), vec![0x80, 0x7F]);
+        assert_eq!(129_i32.encode_leb128_vec(), vec![0x81, 0x01]);
+        assert_eq!((-129_i32).encode_leb128_vec(), vec![0xFF, 0x7E]);
+
+        assert_eq!(0_i64.encode_leb128_vec(), vec![0]);
+        assert_eq!((-1_i64).encode_leb128_vec(), vec![0x7F]);
+        assert_eq!(63_i8.encode_leb128_vec(), vec![0x3F]);
+        assert_eq!(64_i8.encode_leb128_vec(), vec![0xC0, 0x00]);
+        assert_eq!((-64_i8).encode_leb128_vec(), vec![0x40]);
+        assert_eq!(i8::MIN.encode_leb128_vec(), vec![0x80, 0x7F]);
+        assert_eq!(
+            i32::MIN.encode_leb128_vec(),
+            vec![0x80, 0x80, 0x80, 0x80, 0x78]
+        );
+        assert_eq!(
+            i64::MIN.encode_leb128_vec(),
+            vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F]
+        );
+        assert_eq!(i128::MIN.encode_leb128_vec().len(), i128::MAX_ENCODED_SIZE);
+    }
+
+    fn roundtrip<L: Leb128 + PartialEq + core::fmt::Debug>(v: L) {
+        let mut buf = [0_u8; 19];
+        let len = v.encode_leb128(&mut buf);
+        assert_eq!(len, v.leb128_required_space());
+        assert_eq!(L::decode_leb128(&buf[..len]), Some((v, len)));
+
+        for padded in len..=L::MAX_ENCODED_SIZE {
+            assert_eq!(v.encode_leb128_padded(&mut buf, padded), padded);
+            assert_eq!(L::decode_leb128(&buf[..padded]), Some((v, padded)));
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for v in u8::MIN..=u8::MAX {
+            roundtrip(v);
+        }
+        for v in i8::MIN..=i8::MAX {
+            roundtrip(v);
+        }
+        for v in i16::MIN..=i16::MAX {
+            roundtrip(v);
+            roundtrip(v as u16);
+        }
+        for shift in 0..64 {
+            roundtrip(1_u64 << shift);
+            roundtrip((1_i64 << shift).wrapping_sub(1));
+            roundtrip((1_i64 << shift).wrapping_neg());
+            roundtrip((1_u32 << (shift % 32)) as i32);
+        }
+        roundtrip(u128::MAX);
+        roundtrip(i128::MIN);
+        roundtrip(i128::MAX);
+        roundtrip(usize::MAX);
+        roundtrip(isize::MIN);
+    }
+
+    #[test]
+    fn test_encode_padded() {
+        let mut buf = [0_u8; 10];
+        assert_eq!(0_u32.encode_leb128_padded(&mut buf, 5), 5);
+        assert_eq!(&buf[..5], &[0x80, 0x80, 0x80, 0x80, 0x00]);
+        assert_eq!((-1_i32).encode_leb128_padded(&mut buf, 3), 3);
+        assert_eq!(&buf[..3], &[0xFF, 0xFF, 0x7F]);
+        assert_eq!(1_i64.encode_leb128_padded(&mut buf, 2), 2);
+        assert_eq!(&buf[..2], &[0x81, 0x00]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_encode_padded_too_short() {
+        let mut buf = [0_u8; 5];
+        300_u32.encode_leb128_padded(&mut buf, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_encode_padded_too_long() {
+        let mut buf = [0_u8; 10];
+        0_u32.encode_leb128_padded(&mut buf, 6);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        // Longer than the maximum length, even if the value would fit.
+        assert_eq!(
+            u32::try_decode_leb128(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]),
+            Err(DecodeError::Overflow)
+        );
+        assert_eq!(
+            i8::try_decode_leb128(&[0xFF, 0xFF, 0x7F]),
+            Err(DecodeError::Overflow)
+        );
+        // Unused bits of the last byte must be zero...
+        assert_eq!(
+            u32::try_decode_leb128(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F]),
+            Err(DecodeError::OutOfRange)
+        );
+        assert_eq!(
+            u8::try_decode_leb128(&[0x80, 0x02]),
+            Err(DecodeError::OutOfRange)
+        );
+        // ...or equal to the sign bit.
+        assert_eq!(
+            i32::try_decode_leb128(&[0xFF, 0xFF, 0xFF, 0xFF, 0x4F]),
+            Err(DecodeError::OutOfRange)
+        );
+        assert_eq!(
+            i32::try_decode_leb128(&[0x80, 0x80, 0x80, 0x80, 0x70]),
+            Err(DecodeError::OutOfRange)
+        );
+        assert_eq!(
+            i8::try_decode_leb128(&[0x80, 0x7E]),
+            Err(DecodeError::OutOfRange)
+        );
+        assert_eq!(i8::try_decode_leb128(&[0xFF, 0x7F]), Ok((-1, 2)));
+        assert_eq!(i8::try_decode_leb128(&[0xFF, 0x00]), Ok((127, 2)));
+
+        assert_eq!(
+            u64::try_decode_leb128(&[0x80, 0x80]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(i64::try_decode_leb128(&[]), Err(DecodeError::UnexpectedEnd));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_writer() {
+        let mut buf = vec![];
+        assert_eq!(buf.write_leb128(-129_i32).unwrap(), 2);
+        assert_eq!(buf.write_leb128(300_u64).unwrap(), 2);
+        assert_eq!(buf.write_leb128_padded(7_u32, 5).unwrap(), 5);
+        buf.extend_from_slice(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]);
+
+        let mut r: &[u8] = &buf;
+        assert_eq!(r.read_leb128::<i32>().unwrap(), -129);
+        assert_eq!(r.read_leb128::<u64>().unwrap(), 300);
+        assert_eq!(r.read_leb128::<u32>().unwrap(), 7);
+
+        // Too long for a u32: only the allowed number of bytes is consumed.
+        let e = r.read_leb128::<u32>().unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            e.into_inner().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::Overflow)
+        );
+        assert_eq!(r, &[0x00]);
+        assert_eq!(r.read_leb128::<u32>().unwrap(), 0);
+
+        assert_eq!(
+            r.read_leb128::<u32>().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        let mut r: &[u8] = &[0x80];
+        assert_eq!(
+            r.read_leb128::<i64>().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    #[tokio::test]
+    async fn test_async_reader_writer() {
+        let mut buf = vec![];
+        assert_eq!(buf.write_leb128_async(i64::MIN).await.unwrap(), 10);
+        assert_eq!(buf.write_leb128_padded_async(1_u16, 3).await.unwrap(), 3);
+
+        let mut r: &[u8] = &buf;
+        assert_eq!(r.read_leb128_async::<i64>().await.unwrap(), i64::MIN);
+        assert_eq!(r.read_leb128_async::<u16>().await.unwrap(), 1);
+        assert_eq!(
+            r.read_leb128_async::<u16>().await.unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
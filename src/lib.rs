@@ -18,33 +18,153 @@
 //!     assert_eq!(4, encoded_byte_slice.len());
 //!
 //!     let b: i32 = -111;
-//!     let encoded_byte_vec = b.encode_var_vec();
-//!     assert_eq!(Some((b, 2)), i32::decode_var(&encoded_byte_vec));
+//!     let mut encoded_bytes = [0_u8; 10];
+//!     let len = b.encode_var(&mut encoded_bytes);
+//!     assert_eq!(Some((b, 2)), i32::decode_var(&encoded_bytes[..len]));
 //! }
 //! ```
+//!
+//! The crate is `#![no_std]` unless the default `std` feature is enabled. Without `std`, the
+//! [`VarInt`] and [`FixedInt`] traits are still available, but the readers and writers are not;
+//! the `alloc` feature enables the `Vec`-returning helpers like `encode_var_vec()`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+// Lets the code generated by the derive macros refer to `::integer_encoding` in this crate's tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as integer_encoding;
+
+#[cfg(feature = "bytes")]
+mod buf;
+#[cfg(feature = "bytes")]
+mod buf_tests;
+
+#[cfg(feature = "tokio_async")]
+mod codec;
+#[cfg(feature = "tokio_async")]
+mod codec_tests;
+
 #[forbid(unsafe_code)]
 mod fixed;
 mod fixed_tests;
 
+mod leb128;
+mod leb128_tests;
+
+mod twos_complement;
+mod twos_complement_tests;
+
 mod varint;
 mod varint_tests;
 
+mod wire;
+mod wire_tests;
+
+mod zigzag;
+mod zigzag_tests;
+
+mod encode;
+#[cfg(feature = "derive")]
+mod encode_tests;
+mod error;
+#[cfg(feature = "alloc")]
+mod group_varint;
+#[cfg(feature = "alloc")]
+mod group_varint_tests;
+mod iter;
+mod ordered;
+mod ordered_tests;
+mod packed;
+mod packed_tests;
+mod prefix_varint;
+mod prefix_varint_tests;
+mod processor;
+#[cfg(feature = "std")]
 mod reader;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(all(feature = "serde", feature = "std"))]
+mod serde_tests;
+#[cfg(feature = "alloc")]
+mod simd;
+#[cfg(feature = "alloc")]
+mod simd_tests;
+#[cfg(feature = "alloc")]
+mod stream_vbyte;
+#[cfg(feature = "alloc")]
+mod stream_vbyte_tests;
+#[cfg(feature = "std")]
 mod writer;
 
+#[cfg(feature = "bytes")]
+pub use buf::{FixedIntBuf, FixedIntBufMut, VarIntBuf, VarIntBufMut};
+#[cfg(feature = "tokio_async")]
+pub use codec::{VarIntLengthCodec, DEFAULT_MAX_FRAME_LENGTH};
+#[doc(hidden)]
+pub use encode::__private;
+pub use encode::{VarIntDecode, VarIntEncode};
+pub use error::DecodeError;
 pub use fixed::FixedInt;
-pub use varint::VarInt;
+#[cfg(feature = "alloc")]
+pub use group_varint::{decode_group_varint, encode_group_varint, group_varint_required_space};
+#[cfg(feature = "std")]
+pub use group_varint::{GroupVarIntReader, GroupVarIntWriter};
+#[cfg(feature = "derive")]
+pub use integer_encoding_derive::{VarIntDecode, VarIntEncode};
+pub use iter::VarIntIter;
+pub use leb128::Leb128;
+pub use ordered::OrderedInt;
+#[cfg(feature = "alloc")]
+pub use packed::{
+    decode_packed_fixed, decode_packed_varint, encode_packed_fixed, encode_packed_varint,
+};
+pub use packed::{packed_fixed_iter, packed_varint_iter};
+pub use prefix_varint::PrefixVarInt;
+pub use processor::VarIntProcessor;
+#[cfg(feature = "alloc")]
+pub use simd::{decode_var_slice_u32, decode_var_slice_u64};
+#[cfg(feature = "alloc")]
+pub use stream_vbyte::{decode_stream_vbyte, encode_stream_vbyte, stream_vbyte_required_space};
+pub use twos_complement::TwosComplement;
+#[cfg(feature = "alloc")]
+pub use varint::{decode_var_slice, encode_var_slice};
+pub use varint::{required_space_slice, VarInt};
+pub use wire::{
+    decode_key, encode_key, key_required_space, skip_field, WireType, MAX_FIELD_NUMBER,
+};
+pub use zigzag::ZigZag;
 
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
 pub use reader::FixedIntAsyncReader;
+#[cfg(feature = "std")]
 pub use reader::FixedIntReader;
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+pub use reader::Leb128AsyncReader;
+#[cfg(feature = "std")]
+pub use reader::Leb128Reader;
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+pub use reader::PrefixVarIntAsyncReader;
+#[cfg(feature = "std")]
+pub use reader::PrefixVarIntReader;
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
 pub use reader::VarIntAsyncReader;
+#[cfg(feature = "std")]
 pub use reader::VarIntReader;
 
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
 pub use writer::FixedIntAsyncWriter;
+#[cfg(feature = "std")]
 pub use writer::FixedIntWriter;
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+pub use writer::Leb128AsyncWriter;
+#[cfg(feature = "std")]
+pub use writer::Leb128Writer;
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+pub use writer::PrefixVarIntAsyncWriter;
+#[cfg(feature = "std")]
+pub use writer::PrefixVarIntWriter;
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
 pub use writer::VarIntAsyncWriter;
+#[cfg(feature = "std")]
 pub use writer::VarIntWriter;
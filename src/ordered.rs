@@ -0,0 +1,205 @@
+//! Order-preserving integer encodings, see [`OrderedInt`].
+//!
+//! For two values `a` and `b` of the same type, `a < b` holds exactly if the encoding of `a`
+//! sorts before that of `b` when compared bytewise (as `&[u8]`), for both the fixed width and the
+//! variable length form. Encodings of different types or forms must not be compared with each
+//! other.
+//!
+//! This includes negative numbers: all negative values sort before zero and all positive values,
+//! and among themselves in numeric order, so `i64::MIN` has the smallest encoding and -1 the
+//! largest of all negative values. Neither [`VarInt`](crate::VarInt) nor
+//! [`PrefixVarInt`](crate::PrefixVarInt) preserve the order of signed integers, as both use
+//! zigzag encoding.
+
+use core::mem::size_of;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::error::DecodeError;
+use crate::fixed::FixedInt;
+
+/// The header byte of the variable length encoding of 0 for signed integers. Non-negative values
+/// with `n` significant bytes have the header `SIGNED_ZERO + n`, negative values `SIGNED_ZERO - 1
+/// - n`.
+const SIGNED_ZERO: u8 = 0x80;
+
+/// Order-preserving ("memcomparable") encodings: comparing the encodings of two values of the same
+/// type bytewise (e.g. as keys in a key-value store) gives the same result as comparing the values.
+///
+/// The fixed width form is the big-endian representation of a value, with the sign bit flipped
+/// for signed integers. It takes [`FixedInt::ENCODED_SIZE`] bytes.
+///
+/// The variable length form is a header byte followed by the significant bytes of the value in
+/// big-endian order; the header encodes the sign and the number of bytes that follow, so that
+/// shorter encodings sort before longer ones for positive values and after them for negative
+/// values. For unsigned integers, the header is the number of bytes, e.g. 300 is encoded as
+/// `[0x02, 0x01, 0x2C]`. For signed integers, the header of a non-negative value is `0x80` plus
+/// the number of bytes; that of a negative value is `0x7F` minus the number of bytes needed for
+/// its one's complement, e.g. -1 is encoded as `[0x7F]`, and -2 as `[0x7E, 0xFE]`. Only the
+/// shortest encoding of a value is accepted when decoding.
+///
+/// ```
+/// use integer_encoding::OrderedInt;
+///
+/// assert!((-2_i32).encode_ordered_fixed() < 1_i32.encode_ordered_fixed());
+/// assert_eq!(i32::decode_ordered_fixed(&[0x80, 0, 0, 1]), Some(1));
+///
+/// let mut buf = [0_u8; 9];
+/// assert_eq!(300_u64.encode_ordered_var(&mut buf), 3);
+/// assert_eq!(&buf[..3], &[0x02, 0x01, 0x2C]);
+/// assert_eq!(u64::decode_ordered_var(&buf), Some((300, 3)));
+/// ```
+pub trait OrderedInt: FixedInt {
+    /// Returns the fixed width order-preserving encoding of self.
+    fn encode_ordered_fixed(self) -> Self::Bytes;
+    /// Decode a value from its fixed width order-preserving encoding.
+    fn decode_ordered_fixed(src: &[u8]) -> Option<Self> {
+        Self::try_decode_ordered_fixed(src).ok()
+    }
+    /// Like `decode_ordered_fixed()`, but returns a [`DecodeError`] if `src` is too short or too
+    /// long.
+    fn try_decode_ordered_fixed(src: &[u8]) -> Result<Self, DecodeError>;
+
+    /// Returns the number of bytes this number needs in its variable length encoding.
+    fn ordered_var_required_space(self) -> usize;
+    /// Encode a value into the slice using the variable length order-preserving encoding. The
+    /// slice must be at least `ordered_var_required_space()` bytes long. Returns the number of
+    /// bytes written.
+    fn encode_ordered_var(self, dst: &mut [u8]) -> usize;
+    /// Decode a value from the slice. Returns the value and the number of bytes read.
+    ///
+    /// Use `try_decode_ordered_var()` to find out why decoding failed.
+    fn decode_ordered_var(src: &[u8]) -> Option<(Self, usize)> {
+        Self::try_decode_ordered_var(src).ok()
+    }
+    /// Like `decode_ordered_var()`, but returns a [`DecodeError`] describing why a value could not
+    /// be decoded: [`DecodeError::UnexpectedEnd`] if `src` is too short,
+    /// [`DecodeError::OutOfRange`] if the header announces a value too large for this type, and
+    /// [`DecodeError::NonCanonical`] if the value is not encoded in the shortest form.
+    fn try_decode_ordered_var(src: &[u8]) -> Result<(Self, usize), DecodeError>;
+
+    /// Helper: Encode a value using the variable length encoding and return it as Vec.
+    #[cfg(feature = "alloc")]
+    fn encode_ordered_var_vec(self) -> Vec<u8> {
+        let mut v = vec![0; self.ordered_var_required_space()];
+        self.encode_ordered_var(&mut v);
+        v
+    }
+}
+
+/// Returns the `len` payload bytes following the header of a variable length encoding.
+#[inline]
+fn payload(src: &[u8], len: usize) -> Result<&[u8], DecodeError> {
+    src.get(1..=len).ok_or(DecodeError::UnexpectedEnd)
+}
+
+macro_rules! impl_ordered_int {
+    ($t:ty, unsigned) => {
+        impl OrderedInt for $t {
+            fn encode_ordered_fixed(self) -> Self::Bytes {
+                self.to_be_bytes()
+            }
+
+            fn try_decode_ordered_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+                Self::try_decode_fixed_be(src)
+            }
+
+            fn ordered_var_required_space(self) -> usize {
+                1 + ((<$t>::BITS - self.leading_zeros()) as usize).div_ceil(8)
+            }
+
+            fn encode_ordered_var(self, dst: &mut [u8]) -> usize {
+                let len = self.ordered_var_required_space();
+                dst[0] = (len - 1) as u8;
+                dst[1..len].copy_from_slice(&self.to_be_bytes()[size_of::<$t>() + 1 - len..]);
+                len
+            }
+
+            fn try_decode_ordered_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let n = *src.first().ok_or(DecodeError::UnexpectedEnd)? as usize;
+                if n > size_of::<$t>() {
+                    return Err(DecodeError::OutOfRange);
+                }
+                let payload = payload(src, n)?;
+                if payload.first() == Some(&0) {
+                    return Err(DecodeError::NonCanonical);
+                }
+
+                let mut buf = [0_u8; size_of::<$t>()];
+                buf[size_of::<$t>() - n..].copy_from_slice(payload);
+                Ok((<$t>::from_be_bytes(buf), n + 1))
+            }
+        }
+    };
+    ($t:ty, $u:ty, signed) => {
+        impl OrderedInt for $t {
+            fn encode_ordered_fixed(self) -> Self::Bytes {
+                (self ^ <$t>::MIN).to_be_bytes()
+            }
+
+            fn try_decode_ordered_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+                Ok(Self::try_decode_fixed_be(src)? ^ <$t>::MIN)
+            }
+
+            fn ordered_var_required_space(self) -> usize {
+                // The significant bytes of the value, or of its one's complement if negative.
+                let magnitude = (if self < 0 { !self } else { self }) as $u;
+                1 + ((<$u>::BITS - magnitude.leading_zeros()) as usize).div_ceil(8)
+            }
+
+            fn encode_ordered_var(self, dst: &mut [u8]) -> usize {
+                let len = self.ordered_var_required_space();
+                dst[0] = if self < 0 {
+                    SIGNED_ZERO - len as u8
+                } else {
+                    SIGNED_ZERO + len as u8 - 1
+                };
+                dst[1..len].copy_from_slice(&self.to_be_bytes()[size_of::<$t>() + 1 - len..]);
+                len
+            }
+
+            fn try_decode_ordered_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let header = *src.first().ok_or(DecodeError::UnexpectedEnd)?;
+                let negative = header < SIGNED_ZERO;
+                let n = if negative {
+                    (SIGNED_ZERO - 1 - header) as usize
+                } else {
+                    (header - SIGNED_ZERO) as usize
+                };
+                if n > size_of::<$t>() {
+                    return Err(DecodeError::OutOfRange);
+                }
+                let payload = payload(src, n)?;
+                // Sign extension bytes are redundant.
+                let extension = if negative { 0xFF } else { 0x00 };
+                if payload.first() == Some(&extension) {
+                    return Err(DecodeError::NonCanonical);
+                }
+
+                let mut buf = [extension; size_of::<$t>()];
+                buf[size_of::<$t>() - n..].copy_from_slice(payload);
+                let v = <$t>::from_be_bytes(buf);
+                // With all bytes present, the payload's sign bit must agree with the header.
+                if (v < 0) != negative {
+                    return Err(DecodeError::OutOfRange);
+                }
+                Ok((v, n + 1))
+            }
+        }
+    };
+}
+
+impl_ordered_int!(u8, unsigned);
+impl_ordered_int!(u16, unsigned);
+impl_ordered_int!(u32, unsigned);
+impl_ordered_int!(u64, unsigned);
+impl_ordered_int!(u128, unsigned);
+impl_ordered_int!(usize, unsigned);
+
+impl_ordered_int!(i8, u8, signed);
+impl_ordered_int!(i16, u16, signed);
+impl_ordered_int!(i32, u32, signed);
+impl_ordered_int!(i64, u64, signed);
+impl_ordered_int!(i128, u128, signed);
+impl_ordered_int!(isize, usize, signed);
@@ -0,0 +1,221 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    #[cfg(feature = "alloc")]
+    use core::fmt::Debug;
+
+    use crate::error::DecodeError;
+    use crate::ordered::OrderedInt;
+
+    /// A small deterministic PRNG (splitmix64), to generate test values.
+    #[cfg(feature = "alloc")]
+    struct SplitMix64(u64);
+
+    #[cfg(feature = "alloc")]
+    impl SplitMix64 {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^ (z >> 31)
+        }
+
+        /// Returns a random value with a random number of significant bits, so that all encoded
+        /// lengths are covered.
+        fn next_u128(&mut self) -> u128 {
+            let v = u128::from(self.next()) << 64 | u128::from(self.next());
+            v >> (self.next() % 128)
+        }
+    }
+
+    /// Checks that the encodings of `values` roundtrip and sort like the values themselves.
+    #[cfg(feature = "alloc")]
+    fn check_ordering<T: OrderedInt + Ord + Debug>(mut values: Vec<T>) {
+        values.sort_unstable();
+        values.dedup();
+
+        let fixed: Vec<_> = values
+            .iter()
+            .map(|v| v.encode_ordered_fixed().as_ref().to_vec())
+            .collect();
+        let var: Vec<_> = values.iter().map(|v| v.encode_ordered_var_vec()).collect();
+
+        for (i, v) in values.iter().enumerate() {
+            assert_eq!(fixed[i].len(), T::ENCODED_SIZE);
+            assert_eq!(T::decode_ordered_fixed(&fixed[i]), Some(*v));
+            assert_eq!(var[i].len(), v.ordered_var_required_space());
+            assert_eq!(T::decode_ordered_var(&var[i]), Some((*v, var[i].len())));
+        }
+        // Bytewise comparison is a total order, so strictly increasing encodings of adjacent
+        // values imply that all pairs compare like the values.
+        for i in 1..values.len() {
+            assert!(
+                fixed[i - 1] < fixed[i],
+                "{:?} < {:?}",
+                values[i - 1],
+                values[i]
+            );
+            assert!(var[i - 1] < var[i], "{:?} < {:?}", values[i - 1], values[i]);
+        }
+    }
+
+    /// Interesting values around the boundaries of the encoded lengths, and random values.
+    #[cfg(feature = "alloc")]
+    fn sample<T: Copy>(from: impl Fn(u128) -> T) -> Vec<T> {
+        let mut rng = SplitMix64(0x5EED);
+        let mut values = vec![];
+        for shift in 0..128 {
+            for delta in 0..3 {
+                let v = 1_u128 << shift;
+                values.push(from(v.wrapping_add(delta)));
+                values.push(from(v.wrapping_sub(delta)));
+                values.push(from(v.wrapping_add(delta).wrapping_neg()));
+                values.push(from(v.wrapping_sub(delta).wrapping_neg()));
+            }
+        }
+        for _ in 0..2000 {
+            let v = rng.next_u128();
+            values.push(from(v));
+            values.push(from(v.wrapping_neg()));
+        }
+        values
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_ordering_exhaustive() {
+        check_ordering((u8::MIN..=u8::MAX).collect());
+        check_ordering((i8::MIN..=i8::MAX).collect());
+        check_ordering((u16::MIN..=u16::MAX).collect());
+        check_ordering((i16::MIN..=i16::MAX).collect());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_ordering_sampled() {
+        check_ordering(sample(|v| v as u32));
+        check_ordering(sample(|v| v as i32));
+        check_ordering(sample(|v| v as u64));
+        check_ordering(sample(|v| v as i64));
+        check_ordering(sample(|v| v));
+        check_ordering(sample(|v| v as i128));
+        check_ordering(sample(|v| v as usize));
+        check_ordering(sample(|v| v as isize));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_ordering_random_pairs() {
+        let mut rng = SplitMix64(42);
+        for _ in 0..10000 {
+            let (a, b) = (rng.next_u128() as i64, rng.next_u128() as i64);
+            assert_eq!(
+                a.encode_ordered_var_vec().cmp(&b.encode_ordered_var_vec()),
+                a.cmp(&b)
+            );
+            assert_eq!(
+                a.encode_ordered_fixed().cmp(&b.encode_ordered_fixed()),
+                a.cmp(&b)
+            );
+            let (a, b) = (a as u64, b as u64);
+            assert_eq!(
+                a.encode_ordered_var_vec().cmp(&b.encode_ordered_var_vec()),
+                a.cmp(&b)
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_fixed() {
+        assert_eq!(0_i32.encode_ordered_fixed(), [0x80, 0, 0, 0]);
+        assert_eq!((-1_i32).encode_ordered_fixed(), [0x7F, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(i16::MIN.encode_ordered_fixed(), [0, 0]);
+        assert_eq!(0x0102_u16.encode_ordered_fixed(), [1, 2]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_var() {
+        assert_eq!(0_u64.encode_ordered_var_vec(), vec![0x00]);
+        assert_eq!(255_u64.encode_ordered_var_vec(), vec![0x01, 0xFF]);
+        assert_eq!(256_u64.encode_ordered_var_vec(), vec![0x02, 0x01, 0x00]);
+        assert_eq!(u128::MAX.encode_ordered_var_vec()[0], 16);
+
+        assert_eq!(0_i64.encode_ordered_var_vec(), vec![0x80]);
+        assert_eq!(1_i64.encode_ordered_var_vec(), vec![0x81, 0x01]);
+        assert_eq!(128_i64.encode_ordered_var_vec(), vec![0x81, 0x80]);
+        assert_eq!((-1_i64).encode_ordered_var_vec(), vec![0x7F]);
+        assert_eq!((-2_i64).encode_ordered_var_vec(), vec![0x7E, 0xFE]);
+        assert_eq!((-256_i64).encode_ordered_var_vec(), vec![0x7E, 0x00]);
+        assert_eq!((-257_i64).encode_ordered_var_vec(), vec![0x7D, 0xFE, 0xFF]);
+        assert_eq!(i8::MIN.encode_ordered_var_vec(), vec![0x7E, 0x80]);
+        assert_eq!(i128::MIN.encode_ordered_var_vec()[0], 0x7F - 16);
+        assert_eq!(i128::MAX.encode_ordered_var_vec()[0], 0x80 + 16);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            u32::try_decode_ordered_fixed(&[0, 0, 1]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            i16::try_decode_ordered_fixed(&[0, 0, 1]),
+            Err(DecodeError::LengthMismatch {
+                expected: 2,
+                actual: 3
+            })
+        );
+
+        assert_eq!(
+            u32::try_decode_ordered_var(&[]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            u32::try_decode_ordered_var(&[2, 1]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            i32::try_decode_ordered_var(&[0x7D, 0xFE]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            u32::try_decode_ordered_var(&[5, 1, 0, 0, 0, 0]),
+            Err(DecodeError::OutOfRange)
+        );
+        assert_eq!(
+            i32::try_decode_ordered_var(&[0x7F - 5, 1, 0, 0, 0, 0]),
+            Err(DecodeError::OutOfRange)
+        );
+        // The sign of the payload disagrees with the header.
+        assert_eq!(
+            i8::try_decode_ordered_var(&[0x81, 0x80]),
+            Err(DecodeError::OutOfRange)
+        );
+        assert_eq!(
+            i8::try_decode_ordered_var(&[0x7E, 0x7F]),
+            Err(DecodeError::OutOfRange)
+        );
+
+        // Overlong encodings.
+        assert_eq!(
+            u32::try_decode_ordered_var(&[1, 0]),
+            Err(DecodeError::NonCanonical)
+        );
+        assert_eq!(
+            i32::try_decode_ordered_var(&[0x82, 0x00, 0x01]),
+            Err(DecodeError::NonCanonical)
+        );
+        assert_eq!(
+            i32::try_decode_ordered_var(&[0x7E, 0xFF]),
+            Err(DecodeError::NonCanonical)
+        );
+
+        // Trailing bytes are not part of the value.
+        assert_eq!(u32::try_decode_ordered_var(&[1, 5, 9]), Ok((5, 2)));
+    }
+}
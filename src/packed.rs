@@ -0,0 +1,135 @@
+//! Packed repeated fields of the protobuf wire format: a varint length in bytes, followed by the
+//! concatenated encoded values.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::error::DecodeError;
+use crate::fixed::FixedInt;
+use crate::iter::VarIntIter;
+use crate::varint::VarInt;
+#[cfg(feature = "alloc")]
+use crate::varint::{decode_var_slice, slice::DecodeVarSlice};
+
+/// Splits a packed field at the start of `src` into its payload and its total encoded length.
+fn split_packed(src: &[u8]) -> Result<(&[u8], usize), DecodeError> {
+    let (len, size) = u64::try_decode_var(src)?;
+    let len = usize::try_from(len).map_err(|_| DecodeError::UnexpectedEnd)?;
+    if len > src.len() - size {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    Ok((&src[size..size + len], size + len))
+}
+
+/// Checks that the payload of a packed fixed field consists of whole `FI`s.
+// `usize::is_multiple_of()` needs Rust 1.87, and older clippy doesn't know this lint.
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+pub(crate) fn check_packed_fixed_len<FI: FixedInt>(len: usize) -> Result<(), DecodeError> {
+    if len % FI::ENCODED_SIZE != 0 {
+        return Err(DecodeError::PackedLength {
+            len,
+            element_size: FI::ENCODED_SIZE,
+        });
+    }
+    Ok(())
+}
+
+/// Appends `values` to `dst` as packed field of varints: their total length in bytes as varint,
+/// followed by the values. Returns the number of bytes appended.
+///
+/// The values are iterated twice, first to compute the length prefix.
+#[cfg(feature = "alloc")]
+pub fn encode_packed_varint<VI, I>(values: I, dst: &mut Vec<u8>) -> usize
+where
+    VI: VarInt,
+    I: IntoIterator<Item = VI>,
+    I::IntoIter: Clone,
+{
+    let values = values.into_iter();
+    let len: usize = values.clone().map(VI::required_space).sum();
+
+    let start = dst.len();
+    dst.resize(start + len.required_space() + len, 0);
+    let mut i = start;
+    i += len.encode_var(&mut dst[i..]);
+    for v in values {
+        i += v.encode_var(&mut dst[i..]);
+    }
+    debug_assert_eq!(i, dst.len());
+    i - start
+}
+
+/// Like [`encode_packed_varint()`], but encodes the values as little-endian [`FixedInt`]s.
+#[cfg(feature = "alloc")]
+pub fn encode_packed_fixed<FI, I>(values: I, dst: &mut Vec<u8>) -> usize
+where
+    FI: FixedInt,
+    I: IntoIterator<Item = FI>,
+    I::IntoIter: Clone,
+{
+    let values = values.into_iter();
+    let len = values.clone().count() * FI::ENCODED_SIZE;
+
+    let start = dst.len();
+    dst.resize(start + len.required_space(), 0);
+    len.encode_var(&mut dst[start..]);
+    dst.reserve(len);
+    for v in values {
+        dst.extend_from_slice(v.encode_fixed_light().as_ref());
+    }
+    dst.len() - start
+}
+
+/// Decodes the packed field of varints at the start of `src`, appending the values to `dst`.
+/// Returns the number of bytes read.
+///
+/// If the field is truncated or any varint fails to decode, `dst` is restored to its previous
+/// length and the error is returned. Like [`decode_var_slice()`], this is implemented for all
+/// integer types and [`TwosComplement`](crate::TwosComplement).
+#[cfg(feature = "alloc")]
+pub fn decode_packed_varint<VI: DecodeVarSlice>(
+    src: &[u8],
+    dst: &mut Vec<VI>,
+) -> Result<usize, DecodeError> {
+    let (payload, size) = split_packed(src)?;
+    decode_var_slice(payload, dst)?;
+    Ok(size)
+}
+
+/// Decodes the packed field of little-endian [`FixedInt`]s at the start of `src`, appending the
+/// values to `dst`. Returns the number of bytes read.
+///
+/// A payload that isn't a multiple of the size of `FI` results in
+/// [`DecodeError::PackedLength`].
+#[cfg(feature = "alloc")]
+pub fn decode_packed_fixed<FI: FixedInt>(
+    src: &[u8],
+    dst: &mut Vec<FI>,
+) -> Result<usize, DecodeError> {
+    let (values, size) = packed_fixed_iter::<FI>(src)?;
+    dst.extend(values);
+    Ok(size)
+}
+
+/// Returns an iterator over the varints in the packed field at the start of `src`, and the
+/// field's total encoded length. The values are decoded lazily, see [`VarIntIter`].
+pub fn packed_varint_iter<VI: VarInt>(
+    src: &[u8],
+) -> Result<(VarIntIter<'_, VI>, usize), DecodeError> {
+    let (payload, size) = split_packed(src)?;
+    Ok((VarIntIter::new(payload), size))
+}
+
+/// Returns an iterator over the little-endian [`FixedInt`]s in the packed field at the start of
+/// `src`, and the field's total encoded length.
+pub fn packed_fixed_iter<FI: FixedInt>(
+    src: &[u8],
+) -> Result<(impl ExactSizeIterator<Item = FI> + '_, usize), DecodeError> {
+    let (payload, size) = split_packed(src)?;
+    check_packed_fixed_len::<FI>(payload.len())?;
+    let values = payload
+        .chunks_exact(FI::ENCODED_SIZE)
+        .map(|chunk| FI::decode_fixed(chunk).unwrap());
+    Ok((values, size))
+}
@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    #[cfg(feature = "std")]
+    use std::io;
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::reader::{FixedIntAsyncReader, VarIntAsyncReader};
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::writer::{FixedIntAsyncWriter, VarIntAsyncWriter};
+
+    use crate::error::DecodeError;
+    #[cfg(feature = "alloc")]
+    use crate::packed::{
+        decode_packed_fixed, decode_packed_varint, encode_packed_fixed, encode_packed_varint,
+    };
+    use crate::packed::{packed_fixed_iter, packed_varint_iter};
+    #[cfg(feature = "std")]
+    use crate::reader::{FixedIntReader, VarIntReader};
+    #[cfg(feature = "std")]
+    use crate::writer::{FixedIntWriter, VarIntWriter};
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_packed_varint() {
+        // Example from the protobuf documentation.
+        let mut buf = vec![0xFF];
+        assert_eq!(encode_packed_varint(vec![3_u32, 270, 86942], &mut buf), 7);
+        assert_eq!(buf, vec![0xFF, 0x06, 0x03, 0x8E, 0x02, 0x9E, 0xA7, 0x05]);
+
+        let mut dst = vec![1_u32];
+        assert_eq!(decode_packed_varint(&buf[1..], &mut dst), Ok(7));
+        assert_eq!(dst, vec![1, 3, 270, 86942]);
+
+        let mut buf = vec![];
+        assert_eq!(encode_packed_varint(Vec::<i64>::new(), &mut buf), 1);
+        assert_eq!(buf, vec![0]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_packed_fixed() {
+        let mut buf = vec![];
+        assert_eq!(encode_packed_fixed([1_u32, 2].iter().copied(), &mut buf), 9);
+        assert_eq!(buf, vec![8, 1, 0, 0, 0, 2, 0, 0, 0]);
+
+        let mut dst: Vec<u32> = vec![];
+        assert_eq!(decode_packed_fixed(&buf, &mut dst), Ok(9));
+        assert_eq!(dst, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_packed_iter() {
+        let buf = [0x06, 0x03, 0x8E, 0x02, 0x9E, 0xA7, 0x05, 0xFF];
+        let (values, size) = packed_varint_iter::<u32>(&buf).unwrap();
+        assert_eq!(size, 7);
+        assert!(values.eq([Ok(3), Ok(270), Ok(86942)].iter().copied()));
+
+        let buf = [8, 1, 0, 0, 0, 2, 0, 0, 0];
+        let (values, size) = packed_fixed_iter::<u16>(&buf).unwrap();
+        assert_eq!(size, 9);
+        assert_eq!(values.len(), 4);
+        assert!(values.eq([1, 0, 2, 0].iter().copied()));
+
+        assert_eq!(
+            packed_fixed_iter::<u64>(&[4, 0, 0, 0, 0]).err(),
+            Some(DecodeError::PackedLength {
+                len: 4,
+                element_size: 8
+            })
+        );
+        assert_eq!(
+            packed_varint_iter::<u32>(&[3, 1, 2]).err(),
+            Some(DecodeError::UnexpectedEnd)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_packed_errors() {
+        let mut dst = vec![7_u32];
+        assert_eq!(
+            decode_packed_varint(&[3, 1, 2], &mut dst),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        // The last varint is cut off by the length prefix.
+        assert_eq!(
+            decode_packed_varint(&[2, 1, 0x80, 1], &mut dst),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(dst, vec![7]);
+
+        let mut dst = vec![0_u32];
+        assert_eq!(
+            decode_packed_fixed(&[5, 1, 0, 0, 0, 2], &mut dst),
+            Err(DecodeError::PackedLength {
+                len: 5,
+                element_size: 4
+            })
+        );
+        assert_eq!(dst, vec![0]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_writer() {
+        let mut buf = vec![];
+        assert_eq!(buf.write_packed_varint(vec![3_u32, 270, 86942]).unwrap(), 7);
+        assert_eq!(buf.write_packed_fixed(vec![-1_i16, 2]).unwrap(), 5);
+        assert_eq!(&buf[7..], &[4, 0xFF, 0xFF, 0x02, 0x00],);
+
+        let mut r: &[u8] = &buf;
+        let mut varints = vec![];
+        assert_eq!(r.read_packed_varint::<u32>(&mut varints, 16).unwrap(), 3);
+        assert_eq!(varints, vec![3, 270, 86942]);
+        let mut fixed = vec![];
+        assert_eq!(r.read_packed_fixed::<i16>(&mut fixed, 16).unwrap(), 2);
+        assert_eq!(fixed, vec![-1, 2]);
+        assert!(r.is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_errors() {
+        let mut dst = vec![1_u32];
+
+        let e = (&[7_u8, 1, 2][..])
+            .read_packed_varint(&mut dst, 6)
+            .unwrap_err();
+        assert_eq!(
+            e.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::FrameTooLarge { len: 7, max: 6 })
+        );
+        let e = (&[3_u8, 1, 2][..])
+            .read_packed_varint(&mut dst, 16)
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+        let e = (&[2_u8, 1, 0x80, 1][..])
+            .read_packed_varint(&mut dst, 16)
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(dst, vec![1]);
+
+        let e = (&[3_u8, 1, 2, 3][..])
+            .read_packed_fixed(&mut dst, 16)
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        let e = (&[8_u8, 1, 2, 3, 4, 5][..])
+            .read_packed_fixed(&mut dst, 16)
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(dst, vec![1]);
+    }
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    #[tokio::test]
+    async fn test_async_reader_writer() {
+        let mut buf = vec![];
+        assert_eq!(
+            buf.write_packed_varint_async(vec![3_u32, 270, 86942])
+                .await
+                .unwrap(),
+            7
+        );
+        assert_eq!(
+            buf.write_packed_fixed_async(vec![-1_i16, 2]).await.unwrap(),
+            5
+        );
+
+        let mut r: &[u8] = &buf;
+        let mut varints = vec![];
+        assert_eq!(
+            r.read_packed_varint_async::<u32>(&mut varints, 16)
+                .await
+                .unwrap(),
+            3
+        );
+        assert_eq!(varints, vec![3, 270, 86942]);
+        let mut fixed = vec![];
+        assert_eq!(
+            r.read_packed_fixed_async::<i16>(&mut fixed, 16)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(fixed, vec![-1, 2]);
+
+        let mut r: &[u8] = &buf[..5];
+        assert!(r.read_packed_varint_async(&mut varints, 16).await.is_err());
+        assert_eq!(varints, vec![3, 270, 86942]);
+    }
+}
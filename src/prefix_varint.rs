@@ -0,0 +1,202 @@
+//! Prefix varints, see [`PrefixVarInt`].
+//!
+//! The format is limited to 64-bit integers: all 256 values of the first byte are taken, either
+//! by values (0 to 240) or by the lengths of encodings with 1 to 8 further bytes (241 to 255), so
+//! there is no room for the longer encodings of 128-bit integers. `PrefixVarInt` is therefore not
+//! implemented for `u128` and `i128`; use [`VarInt`](crate::VarInt) or
+//! [`FixedInt`](crate::FixedInt) for those.
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::error::DecodeError;
+use crate::zigzag::ZigZag;
+
+/// The longest possible encoded prefix varint, in bytes.
+pub(crate) const MAX_PREFIX_VARINT_SIZE: usize = 9;
+
+/// Returns the total length of the prefix varint starting with `first`.
+#[inline]
+pub(crate) fn prefix_var_len(first: u8) -> usize {
+    match first {
+        0..=240 => 1,
+        241..=248 => 2,
+        249 => 3,
+        _ => first as usize - 246,
+    }
+}
+
+/// How many bytes an integer uses when being encoded as a [`PrefixVarInt`].
+#[inline]
+fn required_encoded_space(v: u64) -> usize {
+    match v {
+        0..=240 => 1,
+        241..=2287 => 2,
+        2288..=67823 => 3,
+        _ => 1 + ((u64::BITS - v.leading_zeros()) as usize).div_ceil(8),
+    }
+}
+
+/// Prefix varint encoding, as used by SQLite4: the first byte of an encoded value determines its
+/// total length, so that it can be decoded without looping over the bytes.
+///
+/// Values up to 240 take one byte, values up to 2287 two bytes and values up to 67823 three
+/// bytes. Larger values are encoded as a length byte followed by the value in big-endian order,
+/// taking up to 9 bytes for a `u64`; 128-bit integers are not supported. The encodings of unsigned integers sort lexicographically in
+/// the same order as the values they represent.
+///
+/// Like [`VarInt`](crate::VarInt), signed integers are zigzag-encoded (see
+/// [`ZigZag`](crate::ZigZag)); their encodings don't preserve order.
+///
+/// ```
+/// use integer_encoding::PrefixVarInt;
+///
+/// let mut buf = [0_u8; 9];
+/// assert_eq!(300_u32.encode_prefix_var(&mut buf), 2);
+/// assert_eq!(&buf[..2], &[241, 60]);
+/// assert_eq!(u32::decode_prefix_var(&buf), Some((300, 2)));
+/// ```
+pub trait PrefixVarInt: Sized + Copy {
+    /// Returns the number of bytes this number needs in its encoded form.
+    fn prefix_var_required_space(self) -> usize;
+    /// Decode a value from the slice. Returns the value and the number of bytes read from the
+    /// slice, or `None` if the slice is too short or the value is out of range for this type.
+    ///
+    /// Use `try_decode_prefix_var()` to find out why decoding failed.
+    fn decode_prefix_var(src: &[u8]) -> Option<(Self, usize)> {
+        Self::try_decode_prefix_var(src).ok()
+    }
+    /// Like `decode_prefix_var()`, but returns a [`DecodeError`] describing why a value could not
+    /// be decoded.
+    fn try_decode_prefix_var(src: &[u8]) -> Result<(Self, usize), DecodeError>;
+
+    /// Like `decode_prefix_var()`, but only accepts the shortest possible encoding of a value.
+    fn decode_prefix_var_canonical(src: &[u8]) -> Option<(Self, usize)> {
+        Self::try_decode_prefix_var_canonical(src).ok()
+    }
+    /// Like `decode_prefix_var_canonical()`, but returns a [`DecodeError`] describing why a value
+    /// could not be decoded. Overlong encodings result in [`DecodeError::NonCanonical`].
+    fn try_decode_prefix_var_canonical(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (v, size) = Self::try_decode_prefix_var(src)?;
+        if size != v.prefix_var_required_space() {
+            return Err(DecodeError::NonCanonical);
+        }
+        Ok((v, size))
+    }
+    /// Encode a value into the slice. The slice must be at least `prefix_var_required_space()`
+    /// bytes long. The number of bytes taken by the encoded integer is returned.
+    fn encode_prefix_var(self, dst: &mut [u8]) -> usize;
+
+    /// Helper: Encode a value and return the encoded form as Vec.
+    #[cfg(feature = "alloc")]
+    fn encode_prefix_var_vec(self) -> Vec<u8> {
+        let mut v = vec![0; self.prefix_var_required_space()];
+        self.encode_prefix_var(&mut v);
+        v
+    }
+}
+
+macro_rules! impl_prefix_varint {
+    ($t:ty, unsigned) => {
+        impl PrefixVarInt for $t {
+            fn prefix_var_required_space(self) -> usize {
+                required_encoded_space(self as u64)
+            }
+
+            fn try_decode_prefix_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let (n, s) = u64::try_decode_prefix_var(src)?;
+                let n = <Self as core::convert::TryFrom<u64>>::try_from(n)
+                    .map_err(|_| DecodeError::OutOfRange)?;
+                Ok((n, s))
+            }
+
+            fn encode_prefix_var(self, dst: &mut [u8]) -> usize {
+                (self as u64).encode_prefix_var(dst)
+            }
+        }
+    };
+    ($t:ty, signed) => {
+        impl PrefixVarInt for $t {
+            fn prefix_var_required_space(self) -> usize {
+                required_encoded_space((self as i64).zigzag_encode())
+            }
+
+            fn try_decode_prefix_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let (n, s) = u64::try_decode_prefix_var(src)?;
+                let n = <Self as core::convert::TryFrom<i64>>::try_from(i64::zigzag_decode(n))
+                    .map_err(|_| DecodeError::OutOfRange)?;
+                Ok((n, s))
+            }
+
+            fn encode_prefix_var(self, dst: &mut [u8]) -> usize {
+                (self as i64).zigzag_encode().encode_prefix_var(dst)
+            }
+        }
+    };
+}
+
+impl_prefix_varint!(usize, unsigned);
+impl_prefix_varint!(u32, unsigned);
+impl_prefix_varint!(u16, unsigned);
+impl_prefix_varint!(u8, unsigned);
+
+impl_prefix_varint!(isize, signed);
+impl_prefix_varint!(i64, signed);
+impl_prefix_varint!(i32, signed);
+impl_prefix_varint!(i16, signed);
+impl_prefix_varint!(i8, signed);
+
+// The base implementation; all other integer types are first cast to u64.
+
+impl PrefixVarInt for u64 {
+    fn prefix_var_required_space(self) -> usize {
+        required_encoded_space(self)
+    }
+
+    #[inline]
+    fn try_decode_prefix_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let first = *src.first().ok_or(DecodeError::UnexpectedEnd)?;
+        let len = prefix_var_len(first);
+        if src.len() < len {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+
+        let v = match first {
+            0..=240 => u64::from(first),
+            241..=248 => 240 + 256 * u64::from(first - 241) + u64::from(src[1]),
+            249 => 2288 + 256 * u64::from(src[1]) + u64::from(src[2]),
+            _ => {
+                let mut buf = [0_u8; 8];
+                buf[MAX_PREFIX_VARINT_SIZE - len..].copy_from_slice(&src[1..len]);
+                u64::from_be_bytes(buf)
+            }
+        };
+        Ok((v, len))
+    }
+
+    #[inline]
+    fn encode_prefix_var(self, dst: &mut [u8]) -> usize {
+        let len = self.prefix_var_required_space();
+        debug_assert!(dst.len() >= len);
+
+        match len {
+            1 => dst[0] = self as u8,
+            2 => {
+                let v = self - 240;
+                dst[0] = (v / 256 + 241) as u8;
+                dst[1] = v as u8;
+            }
+            3 => {
+                let v = self - 2288;
+                dst[0] = 249;
+                dst[1] = (v >> 8) as u8;
+                dst[2] = v as u8;
+            }
+            _ => {
+                dst[0] = (len + 246) as u8;
+                dst[1..len].copy_from_slice(&self.to_be_bytes()[MAX_PREFIX_VARINT_SIZE - len..]);
+            }
+        }
+        len
+    }
+}
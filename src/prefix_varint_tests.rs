@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    #[cfg(feature = "std")]
+    use std::io;
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::reader::PrefixVarIntAsyncReader;
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::writer::PrefixVarIntAsyncWriter;
+
+    use crate::error::DecodeError;
+    use crate::prefix_varint::{prefix_var_len, PrefixVarInt};
+    #[cfg(feature = "std")]
+    use crate::reader::PrefixVarIntReader;
+    #[cfg(feature = "std")]
+    use crate::writer::PrefixVarIntWriter;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode() {
+        assert_eq!(0_u64.encode_prefix_var_vec(), vec![0]);
+        assert_eq!(240_u64.encode_prefix_var_vec(), vec![240]);
+        assert_eq!(241_u64.encode_prefix_var_vec(), vec![241, 1]);
+        assert_eq!(2287_u64.encode_prefix_var_vec(), vec![248, 255]);
+        assert_eq!(2288_u64.encode_prefix_var_vec(), vec![249, 0, 0]);
+        assert_eq!(67823_u64.encode_prefix_var_vec(), vec![249, 255, 255]);
+        assert_eq!(67824_u64.encode_prefix_var_vec(), vec![250, 1, 8, 240]);
+        assert_eq!(
+            0xFFFFFF_u64.encode_prefix_var_vec(),
+            vec![250, 255, 255, 255]
+        );
+        assert_eq!(0x1000000_u64.encode_prefix_var_vec(), vec![251, 1, 0, 0, 0]);
+        assert_eq!(
+            u64::MAX.encode_prefix_var_vec(),
+            vec![255, 255, 255, 255, 255, 255, 255, 255, 255]
+        );
+
+        assert_eq!((-1_i32).encode_prefix_var_vec(), vec![1]);
+        assert_eq!(1_i8.encode_prefix_var_vec(), vec![2]);
+        assert_eq!(i64::MIN.encode_prefix_var_vec().len(), 9);
+    }
+
+    #[test]
+    fn test_required_space() {
+        for &(v, len) in &[
+            (0_u64, 1),
+            (240, 1),
+            (241, 2),
+            (2287, 2),
+            (2288, 3),
+            (67823, 3),
+            (67824, 4),
+            (1 << 24, 5),
+            (1 << 32, 6),
+            (1 << 40, 7),
+            (1 << 48, 8),
+            (1 << 56, 9),
+        ] {
+            assert_eq!(v.prefix_var_required_space(), len);
+            let mut encoded = [0_u8; 9];
+            assert_eq!(v.encode_prefix_var(&mut encoded), len);
+            assert_eq!(prefix_var_len(encoded[0]), len);
+        }
+    }
+
+    fn roundtrip<PV: PrefixVarInt + PartialEq + core::fmt::Debug>(v: PV) {
+        let mut buf = [0_u8; 9];
+        let len = v.encode_prefix_var(&mut buf);
+        assert_eq!(len, v.prefix_var_required_space());
+        assert_eq!(PV::decode_prefix_var_canonical(&buf[..len]), Some((v, len)));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for v in 0..=70000_u32 {
+            roundtrip(v);
+        }
+        for v in i16::MIN..=i16::MAX {
+            roundtrip(v);
+        }
+        for shift in 0..64 {
+            roundtrip(1_u64 << shift);
+            roundtrip((1_u64 << shift) - 1);
+            roundtrip((1_i64 << shift).wrapping_neg());
+        }
+        roundtrip(u64::MAX);
+        roundtrip(i64::MAX);
+        roundtrip(usize::MAX);
+        roundtrip(isize::MIN);
+        roundtrip(u8::MAX);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_sorts_lexicographically() {
+        let mut values = vec![];
+        for shift in 0..64 {
+            for delta in 0..3 {
+                values.push((1_u64 << shift).wrapping_add(delta));
+                values.push((1_u64 << shift).wrapping_sub(delta));
+            }
+        }
+        values.extend_from_slice(&[239, 240, 241, 242, 2287, 2288, 67823, 67824, u64::MAX]);
+        values.extend(0..3000);
+        values.sort_unstable();
+        values.dedup();
+
+        let encoded: Vec<Vec<u8>> = values.iter().map(|v| v.encode_prefix_var_vec()).collect();
+        for w in encoded.windows(2) {
+            assert!(w[0] < w[1], "{:?} >= {:?}", w[0], w[1]);
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            u64::try_decode_prefix_var(&[]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            u64::try_decode_prefix_var(&[241]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            u64::try_decode_prefix_var(&[255, 0, 0, 0]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            u8::try_decode_prefix_var(&[241, 16]),
+            Err(DecodeError::OutOfRange)
+        );
+        assert_eq!(
+            i8::try_decode_prefix_var(&[241, 16]),
+            Err(DecodeError::OutOfRange)
+        );
+
+        // Overlong encodings.
+        assert_eq!(u32::try_decode_prefix_var(&[250, 0, 0, 1]), Ok((1, 4)));
+        assert_eq!(
+            u32::try_decode_prefix_var_canonical(&[250, 0, 0, 1]),
+            Err(DecodeError::NonCanonical)
+        );
+        assert_eq!(
+            u32::try_decode_prefix_var_canonical(&[241, 0]),
+            Err(DecodeError::NonCanonical)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_writer() {
+        let mut buf = vec![];
+        assert_eq!(buf.write_prefix_varint(300_u32).unwrap(), 2);
+        assert_eq!(buf.write_prefix_varint(-2_i64).unwrap(), 1);
+        assert_eq!(buf.write_prefix_varint(u64::MAX).unwrap(), 9);
+        buf.extend_from_slice(&[249, 1]);
+
+        let mut r: &[u8] = &buf;
+        assert_eq!(r.read_prefix_varint::<u32>().unwrap(), 300);
+        assert_eq!(r.read_prefix_varint::<i64>().unwrap(), -2);
+        assert_eq!(r.read_prefix_varint::<u64>().unwrap(), u64::MAX);
+
+        let e = r.read_prefix_varint::<u64>().unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(
+            e.into_inner().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::UnexpectedEnd)
+        );
+
+        let mut r: &[u8] = &[241, 16];
+        assert_eq!(
+            r.read_prefix_varint::<u8>().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    #[tokio::test]
+    async fn test_async_reader_writer() {
+        let mut buf = vec![];
+        assert_eq!(buf.write_prefix_varint_async(67824_u32).await.unwrap(), 4);
+        assert_eq!(buf.write_prefix_varint_async(-1_i8).await.unwrap(), 1);
+
+        let mut r: &[u8] = &buf;
+        assert_eq!(r.read_prefix_varint_async::<u32>().await.unwrap(), 67824);
+        assert_eq!(r.read_prefix_varint_async::<i8>().await.unwrap(), -1);
+        assert_eq!(
+            r.read_prefix_varint_async::<u32>()
+                .await
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
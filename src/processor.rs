@@ -0,0 +1,120 @@
+use core::marker::PhantomData;
+
+use crate::error::DecodeError;
+use crate::varint::{VarInt, MAX_VARINT_SIZE, MSB};
+
+/// `VarIntProcessor` encapsulates the logic for decoding a [`VarInt`] incrementally, as its bytes
+/// arrive.
+///
+/// This is useful for push-based parsers, e.g. driven by readiness events or completion callbacks,
+/// where input arrives in chunks of arbitrary size. Use `feed()` to push bytes and receive the
+/// decoded value once the varint is complete:
+///
+/// ```
+/// use integer_encoding::VarIntProcessor;
+///
+/// let mut p = VarIntProcessor::<u32>::new();
+/// // 300 and 1, split across two chunks.
+/// assert_eq!(p.feed(&[0xAC]), Ok((1, None)));
+/// assert_eq!(p.feed(&[0x02, 0x01]), Ok((1, Some(300))));
+/// assert_eq!(p.feed(&[0x01]), Ok((1, Some(1))));
+/// ```
+#[derive(Debug, Clone)]
+pub struct VarIntProcessor<VI> {
+    buf: [u8; MAX_VARINT_SIZE],
+    maxsize: usize,
+    i: usize,
+    _marker: PhantomData<VI>,
+}
+
+impl<VI: VarInt> Default for VarIntProcessor<VI> {
+    fn default() -> VarIntProcessor<VI> {
+        VarIntProcessor::new()
+    }
+}
+
+impl<VI: VarInt> VarIntProcessor<VI> {
+    /// Creates a processor for decoding a varint of type `VI`.
+    pub fn new() -> VarIntProcessor<VI> {
+        VarIntProcessor {
+            buf: [0; MAX_VARINT_SIZE],
+            maxsize: VI::varint_max_size(),
+            i: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn push(&mut self, b: u8) -> Result<(), DecodeError> {
+        if self.i >= self.maxsize {
+            return Err(DecodeError::Overflow);
+        }
+        self.buf[self.i] = b;
+        self.i += 1;
+        Ok(())
+    }
+
+    /// Consumes bytes from `src` until the varint is complete or `src` is exhausted. Returns the
+    /// number of bytes consumed.
+    ///
+    /// Returns [`DecodeError::Overflow`] if the varint is longer than any varint of type `VI`.
+    pub fn push_slice(&mut self, src: &[u8]) -> Result<usize, DecodeError> {
+        self.push_until_finished(src).map_err(|(_, e)| e)
+    }
+
+    /// Like `push_slice()`, but also returns the number of bytes consumed on error.
+    fn push_until_finished(&mut self, src: &[u8]) -> Result<usize, (usize, DecodeError)> {
+        let mut consumed = 0;
+        for &b in src {
+            if self.finished() {
+                break;
+            }
+            self.push(b).map_err(|e| (consumed, e))?;
+            consumed += 1;
+        }
+        Ok(consumed)
+    }
+
+    /// Consumes bytes from `src` like `push_slice()`. If this completes the varint, it is decoded
+    /// and returned, and the processor is reset for the next varint.
+    ///
+    /// Returns the number of bytes consumed from `src` and the decoded value, if any. Errors are
+    /// returned together with the number of bytes consumed from `src` as well: for
+    /// [`DecodeError::Overflow`], these are the bytes pushed before the varint became too long, and
+    /// the byte at that position was not consumed. After an error, the processor must be
+    /// `reset()` before it can be used again, e.g. to resume at `src[consumed..]`.
+    pub fn feed(&mut self, src: &[u8]) -> Result<(usize, Option<VI>), (usize, DecodeError)> {
+        let consumed = self.push_until_finished(src)?;
+        if !self.finished() {
+            return Ok((consumed, None));
+        }
+        let v = self.decode().map_err(|e| (consumed, e))?;
+        self.reset();
+        Ok((consumed, Some(v)))
+    }
+
+    /// Returns true once the last byte of a varint has been pushed.
+    pub fn finished(&self) -> bool {
+        self.i > 0 && (self.buf[self.i - 1] & MSB == 0)
+    }
+
+    /// Returns the number of bytes pushed so far.
+    pub fn buffered(&self) -> usize {
+        self.i
+    }
+
+    /// Decodes the bytes pushed so far. Returns [`DecodeError::UnexpectedEnd`] if the varint is
+    /// not yet complete.
+    pub fn decode(&self) -> Result<VI, DecodeError> {
+        Ok(VI::try_decode_var(&self.buf[0..self.i])?.0)
+    }
+
+    /// Like `decode()`, but rejects varints that are not minimally encoded.
+    pub fn decode_canonical(&self) -> Result<VI, DecodeError> {
+        Ok(VI::try_decode_var_canonical(&self.buf[0..self.i])?.0)
+    }
+
+    /// Discards all bytes pushed so far.
+    pub fn reset(&mut self) {
+        self.i = 0;
+    }
+}
@@ -1,8 +1,14 @@
 use std::io;
 use std::io::{Read, Result};
 
+use crate::error::DecodeError;
 use crate::fixed::FixedInt;
-use crate::varint::{VarInt, VarIntMaxSize, MSB};
+use crate::leb128::Leb128;
+use crate::packed::check_packed_fixed_len;
+use crate::prefix_varint::{prefix_var_len, PrefixVarInt, MAX_PREFIX_VARINT_SIZE};
+use crate::processor::VarIntProcessor;
+use crate::varint::{VarInt, MAX_VARINT_SIZE, MSB};
+use crate::wire::{fixed_size, split_key, GroupStack, WireType};
 
 #[cfg(feature = "tokio_async")]
 use tokio::io::{AsyncRead, AsyncReadExt};
@@ -17,10 +23,49 @@ pub trait VarIntReader {
     /// Returns either the decoded integer, or an error.
     ///
     /// In general, this always reads a whole varint. If the encoded varint's value is bigger
-    /// than the valid value range of `VI`, then the value is truncated.
+    /// than the valid value range of `VI`, an error is returned.
     ///
-    /// On EOF, an [`io::Error`] with [`io::ErrorKind::UnexpectedEof`] is returned.
+    /// On EOF, an [`io::Error`] with [`io::ErrorKind::UnexpectedEof`] is returned. Invalid
+    /// varints result in [`io::ErrorKind::InvalidData`]; in both cases, the error's inner error
+    /// is a [`DecodeError`].
     fn read_varint<VI: VarInt>(&mut self) -> Result<VI>;
+
+    /// Like `read_varint()`, but rejects varints that are not minimally encoded (see
+    /// [`VarInt::decode_var_canonical()`]) with an [`io::ErrorKind::InvalidData`] error wrapping
+    /// [`DecodeError::NonCanonical`].
+    fn read_varint_canonical<VI: VarInt>(&mut self) -> Result<VI>;
+
+    /// Reads a frame consisting of a varint length prefix followed by that many bytes, as
+    /// written by [`VarIntWriter::write_length_delimited()`](crate::VarIntWriter::write_length_delimited),
+    /// and appends the frame's contents to `buf`. Returns the length of the frame.
+    ///
+    /// Frames longer than `max_len` are rejected with an [`io::ErrorKind::InvalidData`] error
+    /// wrapping [`DecodeError::FrameTooLarge`] before anything is allocated. If the reader ends
+    /// before the whole frame was read, [`io::ErrorKind::UnexpectedEof`] is returned. On error,
+    /// `buf` is restored to its previous length.
+    fn read_length_delimited(&mut self, buf: &mut Vec<u8>, max_len: usize) -> Result<usize>;
+
+    /// Reads a protobuf field key (see [`decode_key()`](crate::decode_key)) and returns its field
+    /// number and wire type. Invalid keys result in an [`io::ErrorKind::InvalidData`] error
+    /// wrapping a [`DecodeError`].
+    fn read_key(&mut self) -> Result<(u32, WireType)>;
+
+    /// Skips the value of a protobuf field with `field_number` and `wire_type`, without
+    /// allocating; the key must already have been read. This works like
+    /// [`skip_field()`](crate::skip_field), and reports errors like `read_varint()`.
+    fn skip_field(&mut self, field_number: u32, wire_type: WireType) -> Result<()>;
+
+    /// Reads a packed repeated field of varints, as written by
+    /// [`VarIntWriter::write_packed_varint()`](crate::VarIntWriter::write_packed_varint), and
+    /// appends the values to `dst`. Returns the number of values read.
+    ///
+    /// Fields longer than `max_len` bytes are rejected as in `read_length_delimited()`. On error,
+    /// `dst` is restored to its previous length.
+    fn read_packed_varint<VI: VarInt>(
+        &mut self,
+        dst: &mut Vec<VI>,
+        max_len: usize,
+    ) -> Result<usize>;
 }
 
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
@@ -28,89 +73,252 @@ pub trait VarIntReader {
 #[async_trait::async_trait(?Send)]
 pub trait VarIntAsyncReader {
     async fn read_varint_async<VI: VarInt>(&mut self) -> Result<VI>;
+    /// Like `read_varint_async()`, but rejects varints that are not minimally encoded.
+    async fn read_varint_canonical_async<VI: VarInt>(&mut self) -> Result<VI>;
+    /// Like `read_length_delimited()`, but asynchronous.
+    async fn read_length_delimited_async(
+        &mut self,
+        buf: &mut Vec<u8>,
+        max_len: usize,
+    ) -> Result<usize>;
+    /// Like `read_key()`, but asynchronous.
+    async fn read_key_async(&mut self) -> Result<(u32, WireType)>;
+    /// Like `skip_field()`, but asynchronous.
+    async fn skip_field_async(&mut self, field_number: u32, wire_type: WireType) -> Result<()>;
+    /// Like `read_packed_varint()`, but asynchronous.
+    async fn read_packed_varint_async<VI: VarInt>(
+        &mut self,
+        dst: &mut Vec<VI>,
+        max_len: usize,
+    ) -> Result<usize>;
 }
 
-/// `VarIntProcessor` encapsulates the logic for decoding a [`VarInt`] byte-by-byte.
-#[derive(Default)]
-pub struct VarIntProcessor {
-    buf: [u8; 10],
-    maxsize: usize,
-    i: usize,
+/// Checks the length prefix of a length-delimited frame against the maximum frame size.
+fn check_frame_len(len: u64, max_len: usize) -> std::result::Result<usize, DecodeError> {
+    if len > max_len as u64 {
+        return Err(DecodeError::FrameTooLarge { len, max: max_len });
+    }
+    Ok(len as usize)
 }
 
-impl VarIntProcessor {
-    fn new<VI: VarIntMaxSize>() -> VarIntProcessor {
-        VarIntProcessor {
-            maxsize: VI::varint_max_size(),
-            ..VarIntProcessor::default()
+/// Restores `dst` to `start` elements if `result` is an error.
+fn truncate_on_error<T>(result: Result<()>, dst: &mut Vec<T>, start: usize) -> Result<usize> {
+    match result {
+        Ok(()) => Ok(dst.len() - start),
+        Err(e) => {
+            dst.truncate(start);
+            Err(e)
         }
     }
-    fn push(&mut self, b: u8) -> Result<()> {
-        if self.i >= self.maxsize {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Unterminated varint",
-            ));
+}
+
+/// Reads the bytes of one varint from `r` into a `VarIntProcessor`.
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+async fn read_varint_bytes_async<VI: VarInt, AR: AsyncRead + Unpin>(
+    r: &mut AR,
+) -> Result<VarIntProcessor<VI>> {
+    let mut buf = [0_u8; 1];
+    let mut p = VarIntProcessor::<VI>::new();
+
+    while !p.finished() {
+        let read = r.read(&mut buf).await?;
+
+        // EOF
+        if read == 0 && p.buffered() == 0 {
+            return Err(io::Error::from(DecodeError::UnexpectedEnd));
         }
-        self.buf[self.i] = b;
-        self.i += 1;
-        Ok(())
-    }
-    fn finished(&self) -> bool {
-        self.i > 0 && (self.buf[self.i - 1] & MSB == 0)
-    }
-    fn decode<VI: VarInt>(&self) -> Option<VI> {
-        Some(VI::decode_var(&self.buf[0..self.i])?.0)
+        if read == 0 {
+            break;
+        }
+
+        p.push(buf[0])?;
     }
+
+    Ok(p)
 }
 
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
 #[async_trait::async_trait(?Send)]
 impl<AR: AsyncRead + Unpin> VarIntAsyncReader for AR {
     async fn read_varint_async<VI: VarInt>(&mut self) -> Result<VI> {
-        let mut buf = [0_u8; 1];
-        let mut p = VarIntProcessor::new::<VI>();
+        let p = read_varint_bytes_async::<VI, _>(self).await?;
+        Ok(p.decode()?)
+    }
+
+    async fn read_varint_canonical_async<VI: VarInt>(&mut self) -> Result<VI> {
+        let p = read_varint_bytes_async::<VI, _>(self).await?;
+        Ok(p.decode_canonical()?)
+    }
+
+    async fn read_length_delimited_async(
+        &mut self,
+        buf: &mut Vec<u8>,
+        max_len: usize,
+    ) -> Result<usize> {
+        let len = check_frame_len(self.read_varint_async().await?, max_len)?;
+        let start = buf.len();
+        let result = match (&mut *self).take(len as u64).read_to_end(buf).await {
+            Ok(read) if read < len => Err(io::Error::from(DecodeError::UnexpectedEnd)),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        };
+        truncate_on_error(result, buf, start)
+    }
 
-        while !p.finished() {
-            let read = self.read(&mut buf).await?;
+    async fn read_key_async(&mut self) -> Result<(u32, WireType)> {
+        Ok(split_key(self.read_varint_async().await?)?)
+    }
 
-            // EOF
-            if read == 0 && p.i == 0 {
-                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Reached EOF"));
+    async fn skip_field_async(&mut self, field_number: u32, wire_type: WireType) -> Result<()> {
+        let mut field_number = field_number;
+        let mut wire_type = wire_type;
+        let mut groups = GroupStack::new();
+        let mut buf = [0_u8; 256];
+
+        loop {
+            match wire_type {
+                WireType::VarInt => {
+                    self.read_varint_async::<u64>().await?;
+                }
+                WireType::I64 | WireType::I32 | WireType::LengthDelimited => {
+                    let mut len = match fixed_size(wire_type) {
+                        Some(len) => len as u64,
+                        None => self.read_varint_async().await?,
+                    };
+                    while len > 0 {
+                        let chunk = len.min(buf.len() as u64) as usize;
+                        let read = self.read(&mut buf[..chunk]).await?;
+                        if read == 0 {
+                            return Err(io::Error::from(DecodeError::UnexpectedEnd));
+                        }
+                        len -= read as u64;
+                    }
+                }
+                WireType::StartGroup => groups.start(field_number)?,
+                WireType::EndGroup => groups.end(field_number)?,
             }
-            if read == 0 {
-                break;
+
+            if groups.is_empty() {
+                return Ok(());
             }
+            (field_number, wire_type) = self.read_key_async().await?;
+        }
+    }
+
+    async fn read_packed_varint_async<VI: VarInt>(
+        &mut self,
+        dst: &mut Vec<VI>,
+        max_len: usize,
+    ) -> Result<usize> {
+        let len = check_frame_len(self.read_varint_async().await?, max_len)?;
+        let start = dst.len();
+        let mut payload = (&mut *self).take(len as u64);
+        let result = async {
+            while payload.limit() > 0 {
+                dst.push(payload.read_varint_async().await?);
+            }
+            Ok(())
+        }
+        .await;
+        truncate_on_error(result, dst, start)
+    }
+}
 
-            p.push(buf[0])?;
+/// Reads the bytes of one varint from `r` into a `VarIntProcessor`.
+fn read_varint_bytes<VI: VarInt, R: Read>(r: &mut R) -> Result<VarIntProcessor<VI>> {
+    let mut buf = [0_u8; 1];
+    let mut p = VarIntProcessor::<VI>::new();
+
+    while !p.finished() {
+        let read = r.read(&mut buf)?;
+
+        // EOF
+        if read == 0 && p.buffered() == 0 {
+            return Err(io::Error::from(DecodeError::UnexpectedEnd));
+        }
+        if read == 0 {
+            break;
         }
 
-        p.decode()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "Reached EOF"))
+        p.push(buf[0])?;
     }
+
+    Ok(p)
 }
 
 impl<R: Read> VarIntReader for R {
     fn read_varint<VI: VarInt>(&mut self) -> Result<VI> {
-        let mut buf = [0_u8; 1];
-        let mut p = VarIntProcessor::new::<VI>();
+        let p = read_varint_bytes::<VI, _>(self)?;
+        Ok(p.decode()?)
+    }
 
-        while !p.finished() {
-            let read = self.read(&mut buf)?;
+    fn read_varint_canonical<VI: VarInt>(&mut self) -> Result<VI> {
+        let p = read_varint_bytes::<VI, _>(self)?;
+        Ok(p.decode_canonical()?)
+    }
 
-            // EOF
-            if read == 0 && p.i == 0 {
-                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Reached EOF"));
-            }
-            if read == 0 {
-                break;
+    fn read_length_delimited(&mut self, buf: &mut Vec<u8>, max_len: usize) -> Result<usize> {
+        let len = check_frame_len(self.read_varint()?, max_len)?;
+        let start = buf.len();
+        // Don't trust the length prefix for allocating: the reader may end much earlier.
+        let result = match self.take(len as u64).read_to_end(buf) {
+            Ok(read) if read < len => Err(io::Error::from(DecodeError::UnexpectedEnd)),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        };
+        truncate_on_error(result, buf, start)
+    }
+
+    fn read_key(&mut self) -> Result<(u32, WireType)> {
+        Ok(split_key(self.read_varint()?)?)
+    }
+
+    fn skip_field(&mut self, field_number: u32, wire_type: WireType) -> Result<()> {
+        let mut field_number = field_number;
+        let mut wire_type = wire_type;
+        let mut groups = GroupStack::new();
+
+        loop {
+            match wire_type {
+                WireType::VarInt => {
+                    self.read_varint::<u64>()?;
+                }
+                WireType::I64 | WireType::I32 | WireType::LengthDelimited => {
+                    let len = match fixed_size(wire_type) {
+                        Some(len) => len as u64,
+                        None => self.read_varint()?,
+                    };
+                    let skipped = io::copy(&mut self.take(len), &mut io::sink())?;
+                    if skipped < len {
+                        return Err(io::Error::from(DecodeError::UnexpectedEnd));
+                    }
+                }
+                WireType::StartGroup => groups.start(field_number)?,
+                WireType::EndGroup => groups.end(field_number)?,
             }
 
-            p.push(buf[0])?;
+            if groups.is_empty() {
+                return Ok(());
+            }
+            (field_number, wire_type) = self.read_key()?;
         }
+    }
 
-        p.decode()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "Reached EOF"))
+    fn read_packed_varint<VI: VarInt>(
+        &mut self,
+        dst: &mut Vec<VI>,
+        max_len: usize,
+    ) -> Result<usize> {
+        let len = check_frame_len(self.read_varint()?, max_len)?;
+        let start = dst.len();
+        let mut payload = self.take(len as u64);
+        let result = (|| {
+            while payload.limit() > 0 {
+                dst.push(payload.read_varint()?);
+            }
+            Ok(())
+        })();
+        truncate_on_error(result, dst, start)
     }
 }
 
@@ -120,6 +328,22 @@ pub trait FixedIntReader {
     ///
     /// On EOF, an [`io::Error`] with [`io::ErrorKind::UnexpectedEof`] is returned.
     fn read_fixedint<FI: FixedInt>(&mut self) -> Result<FI>;
+    /// Like `read_fixedint()`, but reads a big-endian integer.
+    fn read_fixedint_be<FI: FixedInt>(&mut self) -> Result<FI>;
+
+    /// Reads a packed repeated field of little-endian fixed integers, as written by
+    /// [`FixedIntWriter::write_packed_fixed()`](crate::FixedIntWriter::write_packed_fixed), and
+    /// appends the values to `dst`. Returns the number of values read.
+    ///
+    /// Fields longer than `max_len` bytes are rejected as in
+    /// [`VarIntReader::read_length_delimited()`], fields whose length is not a multiple of the
+    /// size of `FI` with [`DecodeError::PackedLength`]. On error, `dst` is restored to its
+    /// previous length.
+    fn read_packed_fixed<FI: FixedInt>(
+        &mut self,
+        dst: &mut Vec<FI>,
+        max_len: usize,
+    ) -> Result<usize>;
 }
 
 /// Like FixedIntReader, but returns a future.
@@ -127,23 +351,185 @@ pub trait FixedIntReader {
 #[async_trait::async_trait(?Send)]
 pub trait FixedIntAsyncReader {
     async fn read_fixedint_async<FI: FixedInt>(&mut self) -> Result<FI>;
+    /// Like `read_fixedint_async()`, but reads a big-endian integer.
+    async fn read_fixedint_be_async<FI: FixedInt>(&mut self) -> Result<FI>;
+    /// Like `read_packed_fixed()`, but asynchronous.
+    async fn read_packed_fixed_async<FI: FixedInt>(
+        &mut self,
+        dst: &mut Vec<FI>,
+        max_len: usize,
+    ) -> Result<usize>;
 }
 
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
 #[async_trait::async_trait(?Send)]
 impl<AR: AsyncRead + Unpin> FixedIntAsyncReader for AR {
     async fn read_fixedint_async<FI: FixedInt>(&mut self) -> Result<FI> {
-        let mut buf = [0_u8; 8];
+        let mut buf = [0_u8; 16];
+        self.read_exact(&mut buf[0..std::mem::size_of::<FI>()])
+            .await?;
+        Ok(FI::try_decode_fixed(&buf[0..std::mem::size_of::<FI>()])?)
+    }
+
+    async fn read_fixedint_be_async<FI: FixedInt>(&mut self) -> Result<FI> {
+        let mut buf = [0_u8; 16];
         self.read_exact(&mut buf[0..std::mem::size_of::<FI>()])
             .await?;
-        Ok(FI::decode_fixed(&buf[0..std::mem::size_of::<FI>()]).unwrap())
+        Ok(FI::try_decode_fixed_be(&buf[0..std::mem::size_of::<FI>()])?)
+    }
+
+    async fn read_packed_fixed_async<FI: FixedInt>(
+        &mut self,
+        dst: &mut Vec<FI>,
+        max_len: usize,
+    ) -> Result<usize> {
+        let len = check_frame_len(self.read_varint_async().await?, max_len)?;
+        check_packed_fixed_len::<FI>(len)?;
+        let start = dst.len();
+        let result = async {
+            for _ in 0..len / FI::ENCODED_SIZE {
+                dst.push(self.read_fixedint_async().await?);
+            }
+            Ok(())
+        }
+        .await;
+        truncate_on_error(result, dst, start)
     }
 }
 
 impl<R: Read> FixedIntReader for R {
     fn read_fixedint<FI: FixedInt>(&mut self) -> Result<FI> {
-        let mut buf = [0_u8; 8];
+        let mut buf = [0_u8; 16];
+        self.read_exact(&mut buf[0..std::mem::size_of::<FI>()])?;
+        Ok(FI::try_decode_fixed(&buf[0..std::mem::size_of::<FI>()])?)
+    }
+
+    fn read_fixedint_be<FI: FixedInt>(&mut self) -> Result<FI> {
+        let mut buf = [0_u8; 16];
         self.read_exact(&mut buf[0..std::mem::size_of::<FI>()])?;
-        Ok(FI::decode_fixed(&buf[0..std::mem::size_of::<FI>()]).unwrap())
+        Ok(FI::try_decode_fixed_be(&buf[0..std::mem::size_of::<FI>()])?)
+    }
+
+    fn read_packed_fixed<FI: FixedInt>(
+        &mut self,
+        dst: &mut Vec<FI>,
+        max_len: usize,
+    ) -> Result<usize> {
+        let len = check_frame_len(self.read_varint()?, max_len)?;
+        check_packed_fixed_len::<FI>(len)?;
+        let start = dst.len();
+        let result = (|| {
+            for _ in 0..len / FI::ENCODED_SIZE {
+                dst.push(self.read_fixedint()?);
+            }
+            Ok(())
+        })();
+        truncate_on_error(result, dst, start)
+    }
+}
+
+/// A trait for reading [`Leb128`] integers from any other `Reader`.
+///
+/// It's recommended to use a buffered reader, as many small reads will happen.
+pub trait Leb128Reader {
+    /// Reads one LEB128 integer, reading at most `L::MAX_ENCODED_SIZE` bytes.
+    ///
+    /// Errors are reported like in [`VarIntReader::read_varint()`]; see
+    /// [`Leb128::try_decode_leb128()`] for the possible [`DecodeError`]s.
+    fn read_leb128<L: Leb128>(&mut self) -> Result<L>;
+}
+
+/// Like Leb128Reader, but returns a future.
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+#[async_trait::async_trait(?Send)]
+pub trait Leb128AsyncReader {
+    async fn read_leb128_async<L: Leb128>(&mut self) -> Result<L>;
+}
+
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+#[async_trait::async_trait(?Send)]
+impl<AR: AsyncRead + Unpin> Leb128AsyncReader for AR {
+    async fn read_leb128_async<L: Leb128>(&mut self) -> Result<L> {
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
+        let mut len = 0;
+        while len < L::MAX_ENCODED_SIZE {
+            if self.read(&mut buf[len..len + 1]).await? == 0 {
+                return Err(io::Error::from(DecodeError::UnexpectedEnd));
+            }
+            len += 1;
+            if buf[len - 1] & MSB == 0 {
+                break;
+            }
+        }
+        Ok(L::try_decode_leb128(&buf[..len])?.0)
+    }
+}
+
+impl<R: Read> Leb128Reader for R {
+    fn read_leb128<L: Leb128>(&mut self) -> Result<L> {
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
+        let mut len = 0;
+        while len < L::MAX_ENCODED_SIZE {
+            if self.read(&mut buf[len..len + 1])? == 0 {
+                return Err(io::Error::from(DecodeError::UnexpectedEnd));
+            }
+            len += 1;
+            if buf[len - 1] & MSB == 0 {
+                break;
+            }
+        }
+        Ok(L::try_decode_leb128(&buf[..len])?.0)
+    }
+}
+
+/// A trait for reading [`PrefixVarInt`]s from any other `Reader`.
+pub trait PrefixVarIntReader {
+    /// Reads one prefix varint. The first byte determines how many more bytes are read.
+    ///
+    /// Errors are reported like in [`VarIntReader::read_varint()`].
+    fn read_prefix_varint<PV: PrefixVarInt>(&mut self) -> Result<PV>;
+}
+
+/// Like PrefixVarIntReader, but returns a future.
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+#[async_trait::async_trait(?Send)]
+pub trait PrefixVarIntAsyncReader {
+    async fn read_prefix_varint_async<PV: PrefixVarInt>(&mut self) -> Result<PV>;
+}
+
+/// Maps an EOF while reading an encoded integer to [`DecodeError::UnexpectedEnd`].
+fn eof_to_unexpected_end(e: io::Error) -> io::Error {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        io::Error::from(DecodeError::UnexpectedEnd)
+    } else {
+        e
+    }
+}
+
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+#[async_trait::async_trait(?Send)]
+impl<AR: AsyncRead + Unpin> PrefixVarIntAsyncReader for AR {
+    async fn read_prefix_varint_async<PV: PrefixVarInt>(&mut self) -> Result<PV> {
+        let mut buf = [0_u8; MAX_PREFIX_VARINT_SIZE];
+        self.read_exact(&mut buf[..1])
+            .await
+            .map_err(eof_to_unexpected_end)?;
+        let len = prefix_var_len(buf[0]);
+        self.read_exact(&mut buf[1..len])
+            .await
+            .map_err(eof_to_unexpected_end)?;
+        Ok(PV::try_decode_prefix_var(&buf[..len])?.0)
+    }
+}
+
+impl<R: Read> PrefixVarIntReader for R {
+    fn read_prefix_varint<PV: PrefixVarInt>(&mut self) -> Result<PV> {
+        let mut buf = [0_u8; MAX_PREFIX_VARINT_SIZE];
+        self.read_exact(&mut buf[..1])
+            .map_err(eof_to_unexpected_end)?;
+        let len = prefix_var_len(buf[0]);
+        self.read_exact(&mut buf[1..len])
+            .map_err(eof_to_unexpected_end)?;
+        Ok(PV::try_decode_prefix_var(&buf[..len])?.0)
     }
 }
@@ -0,0 +1,118 @@
+//! [serde](https://serde.rs) support: helpers for encoding individual fields as varint or fixed
+//! integers in any format, and a compact binary data format based on [`VarInt`](crate::VarInt) and
+//! [`FixedInt`](crate::FixedInt) encoding.
+//!
+//! # Field helpers
+//!
+//! The [`varint`], [`fixed_le`] and [`fixed_be`] modules can be used with serde's `with` attribute
+//! to store an integer field as the bytes of its encoded form, in any serde format. They don't
+//! require the `std` feature.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Entry {
+//!     #[serde(with = "integer_encoding::serde::varint")]
+//!     id: u64,
+//!     #[serde(with = "integer_encoding::serde::fixed_be")]
+//!     checksum: u32,
+//! }
+//! ```
+//!
+//! # Data format
+//!
+//! With the `std` feature, [`Serializer`] and [`Deserializer`] implement a data format writing to
+//! and reading from `std::io` streams. The format is not self-describing; data can only be
+//! deserialized into the type it was serialized from. Values are encoded as follows:
+//!
+//! * Integers (including `u8`/`i8` and 128 bit integers) are encoded as varint; signed integers use
+//!   zigzag encoding.
+//! * `f32` and `f64` are encoded as little-endian fixed integers of their bit representation.
+//! * `bool` is a single byte, 0 or 1. `char` is encoded as `u32`.
+//! * Strings and byte arrays are prefixed by their length as varint.
+//! * Sequences and maps are prefixed by their number of elements as varint, and must know their
+//!   length when being serialized.
+//! * `Option` is a byte, 0 for `None` or 1 for `Some`, followed by the value if present.
+//! * Structs and tuples are encoded as their fields in order, without any prefix.
+//! * Enum variants are prefixed by their index as varint.
+//!
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use integer_encoding::serde::{from_slice, to_vec};
+//!
+//! let value = (300_u32, -2_i64, String::from("abc"), vec![1.5_f32]);
+//! let encoded = to_vec(&value).unwrap();
+//! assert_eq!(&encoded[..4], &[0xAC, 0x02, 0x03, 0x03]);
+//! assert_eq!(from_slice::<(u32, i64, String, Vec<f32>)>(&encoded).unwrap(), value);
+//! # }
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::serde::de::{Error as _, SeqAccess, Visitor};
+
+use crate::error::DecodeError;
+use crate::varint::MAX_VARINT_SIZE;
+
+#[cfg(feature = "std")]
+mod de;
+#[cfg(feature = "std")]
+mod error;
+pub mod fixed_be;
+pub mod fixed_le;
+#[cfg(feature = "std")]
+mod ser;
+pub mod varint;
+
+#[cfg(feature = "std")]
+pub use self::de::{from_reader, from_slice, Deserializer};
+#[cfg(feature = "std")]
+pub use self::error::{Error, Result};
+#[cfg(feature = "std")]
+pub use self::ser::{to_vec, to_writer, Serializer};
+
+/// Deserializes the encoded form of an integer, given either as bytes or, in formats without
+/// native byte arrays, as a sequence of `u8`, and decodes it using `F`.
+struct EncodedVisitor<T, F> {
+    decode: F,
+    _marker: PhantomData<T>,
+}
+
+impl<T, F: FnOnce(&[u8]) -> core::result::Result<T, DecodeError>> EncodedVisitor<T, F> {
+    fn new(decode: F) -> Self {
+        EncodedVisitor {
+            decode,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T, F: FnOnce(&[u8]) -> core::result::Result<T, DecodeError>> Visitor<'de>
+    for EncodedVisitor<T, F>
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "an encoded integer")
+    }
+
+    fn visit_bytes<E: ::serde::de::Error>(self, v: &[u8]) -> core::result::Result<T, E> {
+        (self.decode)(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> core::result::Result<T, A::Error> {
+        // No integer encoding is longer than a varint.
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
+        let mut len = 0;
+        while let Some(b) = seq.next_element()? {
+            if len == buf.len() {
+                return Err(A::Error::invalid_length(len + 1, &self));
+            }
+            buf[len] = b;
+            len += 1;
+        }
+        (self.decode)(&buf[..len]).map_err(A::Error::custom)
+    }
+}
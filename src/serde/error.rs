@@ -0,0 +1,70 @@
+use std::fmt;
+use std::io;
+
+/// Errors that can occur when serializing or deserializing.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading or writing failed, or a varint could not be decoded. In the latter case, the
+    /// `io::Error` wraps a [`DecodeError`](crate::DecodeError).
+    Io(io::Error),
+    /// A sequence or map without known length was serialized.
+    UnknownLength,
+    /// `deserialize_any()` and `deserialize_ignored_any()` are not supported, as the format is not
+    /// self-describing.
+    AnyNotSupported,
+    /// A `bool` was encoded as a byte other than 0 or 1.
+    InvalidBool(u8),
+    /// An `Option` tag was neither 0 nor 1.
+    InvalidOptionTag(u8),
+    /// A `char` was not a valid unicode scalar value.
+    InvalidChar(u32),
+    /// A string was not valid UTF-8.
+    InvalidUtf8,
+    /// A custom error reported by a `Serialize` or `Deserialize` implementation.
+    Message(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::UnknownLength => write!(f, "sequences and maps must have a known length"),
+            Error::AnyNotSupported => write!(f, "format is not self-describing"),
+            Error::InvalidBool(b) => write!(f, "invalid bool: {}", b),
+            Error::InvalidOptionTag(t) => write!(f, "invalid option tag: {}", t),
+            Error::InvalidChar(c) => write!(f, "invalid char: {:#x}", c),
+            Error::InvalidUtf8 => write!(f, "invalid UTF-8 in string"),
+            Error::Message(m) => write!(f, "{}", m),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl ::serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl ::serde::de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error::Message(msg.to_string())
+    }
+}
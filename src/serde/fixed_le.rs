@@ -0,0 +1,19 @@
+//! Serializes an integer as the bytes of its little-endian fixed encoding, as returned by
+//! [`FixedInt::encode_fixed_light()`](crate::FixedInt::encode_fixed_light).
+//!
+//! Use with `#[serde(with = "integer_encoding::serde::fixed_le")]`.
+
+use ::serde::{Deserializer, Serializer};
+
+use super::EncodedVisitor;
+use crate::fixed::FixedInt;
+
+pub fn serialize<FI: FixedInt, S: Serializer>(v: &FI, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(v.encode_fixed_light().as_ref())
+}
+
+pub fn deserialize<'de, FI: FixedInt, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<FI, D::Error> {
+    deserializer.deserialize_bytes(EncodedVisitor::new(FI::try_decode_fixed))
+}
@@ -0,0 +1,30 @@
+//! Serializes an integer as the bytes of its varint encoding, as returned by
+//! [`VarInt::encode_var_vec()`](crate::VarInt::encode_var_vec).
+//!
+//! Use with `#[serde(with = "integer_encoding::serde::varint")]`. Deserialization fails unless the
+//! bytes contain exactly one varint.
+
+use ::serde::{Deserializer, Serializer};
+
+use super::EncodedVisitor;
+use crate::error::DecodeError;
+use crate::varint::{VarInt, MAX_VARINT_SIZE};
+
+pub fn serialize<VI: VarInt, S: Serializer>(v: &VI, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut buf = [0_u8; MAX_VARINT_SIZE];
+    let len = v.encode_var(&mut buf);
+    serializer.serialize_bytes(&buf[..len])
+}
+
+pub fn deserialize<'de, VI: VarInt, D: Deserializer<'de>>(deserializer: D) -> Result<VI, D::Error> {
+    deserializer.deserialize_bytes(EncodedVisitor::new(|src: &[u8]| {
+        let (v, len) = VI::try_decode_var(src)?;
+        if len != src.len() {
+            return Err(DecodeError::LengthMismatch {
+                expected: len,
+                actual: src.len(),
+            });
+        }
+        Ok(v)
+    }))
+}
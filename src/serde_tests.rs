@@ -0,0 +1,290 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::io;
+
+    use serde::de::value::{self, SeqDeserializer};
+    use serde::{Deserialize, Serialize};
+
+    use crate::error::DecodeError;
+    use crate::serde::{from_reader, from_slice, to_vec, to_writer, Error};
+    use crate::varint::VarInt;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rect { w: u32, h: u32 },
+        Line(i32, i32),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        id: u64,
+        delta: i16,
+        big: i128,
+        flag: bool,
+        letter: char,
+        name: String,
+        #[serde(with = "serde_bytes_vec")]
+        data: Vec<u8>,
+        score: f32,
+        parent: Option<Box<Record>>,
+        shapes: Vec<Shape>,
+        tags: BTreeMap<String, u8>,
+        unit: (),
+        pair: (u8, i8),
+    }
+
+    // Serializes a Vec<u8> through serialize_bytes() instead of as a sequence.
+    mod serde_bytes_vec {
+        use serde::de::{Deserializer, Error, Visitor};
+        use serde::Serializer;
+        use std::fmt;
+
+        pub fn serialize<S: Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_bytes(v)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+            struct V;
+            impl<'de> Visitor<'de> for V {
+                type Value = Vec<u8>;
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("bytes")
+                }
+                fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                    Ok(v)
+                }
+            }
+            d.deserialize_byte_buf(V)
+        }
+    }
+
+    fn record() -> Record {
+        let mut tags = BTreeMap::new();
+        tags.insert("a".to_string(), 1);
+        tags.insert("bb".to_string(), 200);
+        Record {
+            id: u64::MAX,
+            delta: -300,
+            big: i128::MIN,
+            flag: true,
+            letter: 'ß',
+            name: "hello wörld".to_string(),
+            data: vec![0, 1, 255],
+            score: -0.25,
+            parent: Some(Box::new(Record {
+                id: 0,
+                delta: 0,
+                big: 0,
+                flag: false,
+                letter: 'a',
+                name: String::new(),
+                data: vec![],
+                score: f32::INFINITY,
+                parent: None,
+                shapes: vec![],
+                tags: BTreeMap::new(),
+                unit: (),
+                pair: (0, 0),
+            })),
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(1.5),
+                Shape::Rect { w: 3, h: 400 },
+                Shape::Line(-1, 1),
+            ],
+            tags,
+            unit: (),
+            pair: (255, -128),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_record() {
+        let r = record();
+        let encoded = to_vec(&r).unwrap();
+        assert_eq!(from_slice::<Record>(&encoded).unwrap(), r);
+
+        let mut w = vec![];
+        to_writer(&mut w, &r).unwrap();
+        assert_eq!(w, encoded);
+        assert_eq!(from_reader::<_, Record>(io::Cursor::new(w)).unwrap(), r);
+    }
+
+    #[test]
+    fn test_encoding() {
+        assert_eq!(to_vec(&300_u32).unwrap(), vec![0xAC, 0x02]);
+        assert_eq!(to_vec(&-1_i32).unwrap(), vec![0x01]);
+        assert_eq!(to_vec(&200_u8).unwrap(), vec![0xC8, 0x01]);
+        assert_eq!(to_vec(&true).unwrap(), vec![1]);
+        assert_eq!(to_vec(&1.0_f32).unwrap(), vec![0x00, 0x00, 0x80, 0x3F]);
+        assert_eq!(to_vec("abc").unwrap(), vec![3, b'a', b'b', b'c']);
+        assert_eq!(to_vec(&vec![1_u16, 2]).unwrap(), vec![2, 1, 2]);
+        assert_eq!(to_vec(&Some(5_u8)).unwrap(), vec![1, 5]);
+        assert_eq!(to_vec(&None::<u8>).unwrap(), vec![0]);
+        assert_eq!(to_vec(&(1_u8, 2_u8)).unwrap(), vec![1, 2]);
+        assert_eq!(to_vec(&Shape::Rect { w: 1, h: 2 }).unwrap(), vec![2, 1, 2]);
+    }
+
+    #[test]
+    fn test_consecutive_values() {
+        let mut w = vec![];
+        to_writer(&mut w, &1_u32).unwrap();
+        to_writer(&mut w, "x").unwrap();
+
+        let mut r = &w[..];
+        assert_eq!(from_reader::<_, u32>(&mut r).unwrap(), 1);
+        assert_eq!(from_reader::<_, String>(&mut r).unwrap(), "x");
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        match from_slice::<bool>(&[2]) {
+            Err(Error::InvalidBool(2)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match from_slice::<Option<u8>>(&[3]) {
+            Err(Error::InvalidOptionTag(3)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match from_slice::<char>(&0xD800_u32.encode_var_vec()) {
+            Err(Error::InvalidChar(0xD800)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match from_slice::<String>(&[1, 0xFF]) {
+            Err(Error::InvalidUtf8) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match from_slice::<String>(&[5, b'a']) {
+            Err(Error::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match from_slice::<u8>(&[0x80, 0x02]) {
+            Err(Error::Io(e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+                assert_eq!(
+                    e.into_inner().unwrap().downcast_ref::<DecodeError>(),
+                    Some(&DecodeError::OutOfRange)
+                );
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match from_slice::<Shape>(&[9]) {
+            Err(Error::Message(_)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_unsupported() {
+        struct Unsized;
+        impl Serialize for Unsized {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                use serde::ser::SerializeSeq;
+                s.serialize_seq(None)?.end()
+            }
+        }
+        match to_vec(&Unsized) {
+            Err(Error::UnknownLength) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        #[serde(untagged)]
+        enum Untagged {
+            A(u8),
+        }
+        match from_slice::<Untagged>(&[1]) {
+            Err(Error::AnyNotSupported) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct WithFields {
+        #[serde(with = "crate::serde::varint")]
+        a: u64,
+        #[serde(with = "crate::serde::varint")]
+        b: i32,
+        #[serde(with = "crate::serde::fixed_le")]
+        c: u32,
+        #[serde(with = "crate::serde::fixed_be")]
+        d: i16,
+    }
+
+    #[test]
+    fn test_with_modules() {
+        let v = WithFields {
+            a: 300,
+            b: -1,
+            c: 0x01020304,
+            d: -2,
+        };
+        let encoded = to_vec(&v).unwrap();
+        assert_eq!(
+            encoded,
+            vec![2, 0xAC, 0x02, 1, 1, 4, 4, 3, 2, 1, 2, 0xFF, 0xFE]
+        );
+        assert_eq!(from_slice::<WithFields>(&encoded).unwrap(), v);
+    }
+
+    // Formats without native byte arrays present the bytes as a sequence of u8.
+    fn from_seq<T, F>(bytes: Vec<u8>, deserialize: F) -> Result<T, value::Error>
+    where
+        F: FnOnce(SeqDeserializer<std::vec::IntoIter<u8>, value::Error>) -> Result<T, value::Error>,
+    {
+        deserialize(SeqDeserializer::new(bytes.into_iter()))
+    }
+
+    #[test]
+    fn test_with_modules_from_seq() {
+        assert_eq!(
+            from_seq(vec![0xAC, 0x02], crate::serde::varint::deserialize),
+            Ok(300_u32)
+        );
+        assert_eq!(
+            from_seq(vec![3], crate::serde::varint::deserialize),
+            Ok(-2_i64)
+        );
+        assert_eq!(
+            from_seq(vec![1, 2], crate::serde::fixed_le::deserialize),
+            Ok(0x0201_u16)
+        );
+        assert_eq!(
+            from_seq(vec![1, 2], crate::serde::fixed_be::deserialize),
+            Ok(0x0102_u16)
+        );
+
+        // Trailing bytes after the varint.
+        assert!(from_seq::<u32, _>(vec![1, 2], crate::serde::varint::deserialize).is_err());
+        // Out of range for the target type.
+        assert!(from_seq::<u8, _>(vec![0x80, 0x02], crate::serde::varint::deserialize).is_err());
+        // Too short for a fixed integer.
+        assert!(from_seq::<u32, _>(vec![1, 2, 3], crate::serde::fixed_le::deserialize).is_err());
+        // Longer than any encoded integer.
+        assert!(from_seq::<u128, _>(vec![0x80; 20], crate::serde::varint::deserialize).is_err());
+    }
+
+    #[test]
+    fn test_with_modules_errors() {
+        match from_slice::<WithFields>(&[1, 0x80, 1, 1, 4, 0, 0, 0, 0, 2, 0, 0]) {
+            Err(Error::Message(m)) => assert_eq!(m, DecodeError::UnexpectedEnd.to_string()),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match from_slice::<WithFields>(&[1, 1, 1, 1, 5, 0, 0, 0, 0, 0, 2, 0, 0]) {
+            Err(Error::Message(m)) => assert_eq!(
+                m,
+                DecodeError::LengthMismatch {
+                    expected: 4,
+                    actual: 5
+                }
+                .to_string()
+            ),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+}
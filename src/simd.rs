@@ -0,0 +1,353 @@
+//! Bulk decoding of `u32`/`u64` varints with the Masked VByte algorithm described by Plaisance,
+//! Kurz and Lemire in "Vectorized VByte Decoding", on x86_64 CPUs supporting SSSE3.
+//!
+//! The decoder loads 16 bytes at once and gathers their MSBs with a single `movemask`
+//! instruction. If no MSB is set, the block consists of 16 single-byte varints. Otherwise, the
+//! MSBs of the first 12 bytes select an entry of a precomputed table, giving a `pshufb` mask and
+//! the number of bytes consumed. The mask moves the bytes of the next six varints of up to two
+//! bytes, four varints of up to three bytes, or two varints of up to five bytes into 16, 32 or
+//! 64 bit lanes, where the 7-bit groups of all lanes are joined by a few shifts at once.
+//!
+//! If the next varint is longer than five bytes or doesn't end within the first 12 bytes, it is
+//! handed to the scalar decoder, which also reports any errors; the results are therefore always
+//! the same as those of the scalar decoder.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::error::DecodeError;
+use crate::varint::{decode_var_slice, decode_var_slice_scalar, VarInt};
+
+#[cfg(target_arch = "x86_64")]
+use crate::stream_vbyte::has_ssse3;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Integer types supported by the accelerated decoder.
+pub(crate) trait BulkVarInt:
+    VarInt + From<u8> + From<u16> + From<u32> + TryFrom<u64>
+{
+}
+
+impl BulkVarInt for u32 {}
+
+impl BulkVarInt for u64 {}
+
+/// Decodes consecutive `u32` varints from `src` until it is exhausted, appending them to `dst`.
+/// Returns the number of values decoded.
+///
+/// This is the same as [`decode_var_slice::<u32>()`](crate::decode_var_slice), which uses SSSE3
+/// if the CPU supports it.
+pub fn decode_var_slice_u32(src: &[u8], dst: &mut Vec<u32>) -> Result<usize, DecodeError> {
+    decode_var_slice(src, dst)
+}
+
+/// Decodes consecutive `u64` varints from `src` until it is exhausted, appending them to `dst`.
+/// Returns the number of values decoded.
+///
+/// This is the same as [`decode_var_slice::<u64>()`](crate::decode_var_slice), which uses SSSE3
+/// if the CPU supports it.
+pub fn decode_var_slice_u64(src: &[u8], dst: &mut Vec<u64>) -> Result<usize, DecodeError> {
+    decode_var_slice(src, dst)
+}
+
+/// Appends the varints in `src` to `dst` using the fastest decoder the CPU supports.
+pub(crate) fn decode_dispatch<T: BulkVarInt>(
+    src: &[u8],
+    dst: &mut Vec<T>,
+) -> Result<(), DecodeError> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if has_ssse3() {
+            // Safety: SSSE3 support was checked above.
+            return unsafe { decode_ssse3(src, dst) };
+        }
+    }
+    decode_var_slice_scalar(src, dst)
+}
+
+/// Number of shuffles decoding six varints of up to two bytes into `u16` lanes; the shuffle for
+/// lengths `l0..l5` is `sum((l_k - 1) << k)`.
+#[cfg(target_arch = "x86_64")]
+const SHUFFLES_U16: usize = 64;
+/// Number of shuffles decoding four varints of up to three bytes into `u32` lanes, following
+/// those for `u16` lanes; the shuffle for lengths `l0..l3` is `SHUFFLES_U16 + sum((l_k - 1) *
+/// 3^k)`.
+#[cfg(target_arch = "x86_64")]
+const SHUFFLES_U32: usize = 81;
+/// Number of shuffles decoding two varints of up to five bytes into `u64` lanes, following those
+/// for `u32` lanes; the shuffle for lengths `l0, l1` is `SHUFFLES_U16 + SHUFFLES_U32 + (l0 - 1) *
+/// 5 + (l1 - 1)`.
+#[cfg(target_arch = "x86_64")]
+const SHUFFLES_U64: usize = 25;
+#[cfg(target_arch = "x86_64")]
+const SHUFFLE_COUNT: usize = SHUFFLES_U16 + SHUFFLES_U32 + SHUFFLES_U64;
+/// Pattern shuffle of blocks decoded by the scalar decoder.
+#[cfg(target_arch = "x86_64")]
+const SCALAR: u8 = u8::MAX;
+
+/// Returns the number of varints decoded by `shuffle`, the size in bytes of their lanes, and
+/// their encoded lengths.
+#[cfg(target_arch = "x86_64")]
+const fn shuffle_lens(shuffle: usize) -> (usize, usize, [usize; 6]) {
+    let mut lens = [0; 6];
+    if shuffle < SHUFFLES_U16 {
+        let mut k = 0;
+        while k < 6 {
+            lens[k] = 1 + ((shuffle >> k) & 1);
+            k += 1;
+        }
+        (6, 2, lens)
+    } else if shuffle < SHUFFLES_U16 + SHUFFLES_U32 {
+        let mut rest = shuffle - SHUFFLES_U16;
+        let mut k = 0;
+        while k < 4 {
+            lens[k] = 1 + rest % 3;
+            rest /= 3;
+            k += 1;
+        }
+        (4, 4, lens)
+    } else {
+        let rest = shuffle - SHUFFLES_U16 - SHUFFLES_U32;
+        lens[0] = 1 + rest / 5;
+        lens[1] = 1 + rest % 5;
+        (2, 8, lens)
+    }
+}
+
+/// `pshufb` masks moving the bytes of consecutive varints into lanes, as described by
+/// `shuffle_lens()`. Lane bytes not belonging to a varint are zeroed (by an index with the MSB
+/// set).
+#[cfg(target_arch = "x86_64")]
+static SHUFFLES: [[u8; 16]; SHUFFLE_COUNT] = {
+    let mut shuffles = [[0x80; 16]; SHUFFLE_COUNT];
+    let mut s = 0;
+    while s < SHUFFLE_COUNT {
+        let (count, width, lens) = shuffle_lens(s);
+        let mut offset = 0;
+        let mut k = 0;
+        while k < count {
+            let mut j = 0;
+            while j < lens[k] {
+                shuffles[s][width * k + j] = (offset + j) as u8;
+                j += 1;
+            }
+            offset += lens[k];
+            k += 1;
+        }
+        s += 1;
+    }
+    shuffles
+};
+
+/// How the varints at the start of a block are decoded.
+#[cfg(target_arch = "x86_64")]
+#[derive(Clone, Copy)]
+struct Pattern {
+    /// Index into `SHUFFLES`, or `SCALAR`.
+    shuffle: u8,
+    /// Number of bytes taken by the varints decoded by `shuffle`.
+    consumed: u8,
+}
+
+/// Returns the longest of the first `n` lengths in `lens`.
+#[cfg(target_arch = "x86_64")]
+const fn max_len(lens: &[usize; 12], n: usize) -> usize {
+    let mut max = 0;
+    let mut k = 0;
+    while k < n {
+        if lens[k] > max {
+            max = lens[k];
+        }
+        k += 1;
+    }
+    max
+}
+
+/// Returns the sum of the first `n` lengths in `lens`.
+#[cfg(target_arch = "x86_64")]
+const fn total_len(lens: &[usize; 12], n: usize) -> u8 {
+    let mut sum = 0;
+    let mut k = 0;
+    while k < n {
+        sum += lens[k];
+        k += 1;
+    }
+    sum as u8
+}
+
+/// Returns the pattern for a block whose first 12 bytes have their MSBs set as in `mask`.
+#[cfg(target_arch = "x86_64")]
+const fn pattern(mask: usize) -> Pattern {
+    // The lengths of the varints ending within the first 12 bytes.
+    let mut lens = [0; 12];
+    let mut count = 0;
+    let mut len = 0;
+    let mut k = 0;
+    while k < 12 {
+        len += 1;
+        if mask & (1 << k) == 0 {
+            lens[count] = len;
+            count += 1;
+            len = 0;
+        }
+        k += 1;
+    }
+
+    if count >= 6 && max_len(&lens, 6) <= 2 {
+        let mut shuffle = 0;
+        let mut k = 0;
+        while k < 6 {
+            shuffle |= (lens[k] - 1) << k;
+            k += 1;
+        }
+        Pattern {
+            shuffle: shuffle as u8,
+            consumed: total_len(&lens, 6),
+        }
+    } else if count >= 4 && max_len(&lens, 4) <= 3 {
+        let mut shuffle = 0;
+        let mut k = 4;
+        while k > 0 {
+            k -= 1;
+            shuffle = shuffle * 3 + (lens[k] - 1);
+        }
+        Pattern {
+            shuffle: (SHUFFLES_U16 + shuffle) as u8,
+            consumed: total_len(&lens, 4),
+        }
+    } else if count >= 2 && max_len(&lens, 2) <= 5 {
+        Pattern {
+            shuffle: (SHUFFLES_U16 + SHUFFLES_U32 + (lens[0] - 1) * 5 + (lens[1] - 1)) as u8,
+            consumed: total_len(&lens, 2),
+        }
+    } else {
+        Pattern {
+            shuffle: SCALAR,
+            consumed: 0,
+        }
+    }
+}
+
+/// The pattern for each combination of MSBs of the first 12 bytes of a block.
+#[cfg(target_arch = "x86_64")]
+static PATTERNS: [Pattern; 1 << 12] = {
+    let mut patterns = [Pattern {
+        shuffle: SCALAR,
+        consumed: 0,
+    }; 1 << 12];
+    let mut mask = 0;
+    while mask < 1 << 12 {
+        patterns[mask] = pattern(mask);
+        mask += 1;
+    }
+    patterns
+};
+
+/// Like `decode_var_slice_scalar()`, but decodes blocks of varints with a single shuffle as long
+/// as 16 bytes can be loaded; the remaining varints are handed to `decode_var_slice_scalar()`.
+///
+/// # Safety
+///
+/// The CPU must support SSSE3.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+pub(crate) unsafe fn decode_ssse3<T: BulkVarInt>(
+    src: &[u8],
+    dst: &mut Vec<T>,
+) -> Result<(), DecodeError> {
+    let mut i = 0;
+    while i + 16 <= src.len() {
+        let bytes = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+        let mask = _mm_movemask_epi8(bytes) as usize;
+        if mask == 0 {
+            dst.extend(src[i..i + 16].iter().map(|&b| T::from(b)));
+            i += 16;
+            continue;
+        }
+
+        let Pattern { shuffle, consumed } = PATTERNS[mask & 0xFFF];
+        let s = shuffle as usize;
+        let decoded = if s < SHUFFLE_COUNT {
+            let shuffle = _mm_loadu_si128(SHUFFLES[s].as_ptr() as *const __m128i);
+            let x = _mm_shuffle_epi8(bytes, shuffle);
+            if s < SHUFFLES_U16 {
+                let mut lanes = [0_u16; 8];
+                _mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, join_u16(x));
+                dst.extend(lanes[..6].iter().map(|&v| T::from(v)));
+                true
+            } else if s < SHUFFLES_U16 + SHUFFLES_U32 {
+                let mut lanes = [0_u32; 4];
+                _mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, join_u32(x));
+                dst.extend(lanes.iter().map(|&v| T::from(v)));
+                true
+            } else {
+                let mut lanes = [0_u64; 2];
+                _mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, join_u64(x));
+                // Five-byte varints may be out of range for `u32`; the scalar decoder reports
+                // that.
+                match (T::try_from(lanes[0]), T::try_from(lanes[1])) {
+                    (Ok(a), Ok(b)) => {
+                        dst.push(a);
+                        dst.push(b);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+        } else {
+            false
+        };
+
+        if decoded {
+            i += consumed as usize;
+        } else {
+            let (v, size) = T::try_decode_var(&src[i..])?;
+            dst.push(v);
+            i += size;
+        }
+    }
+
+    decode_var_slice_scalar(&src[i..], dst)
+}
+
+/// Joins the 7-bit groups of the (up to two bytes long) varints in the `u16` lanes of `x`.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+unsafe fn join_u16(x: __m128i) -> __m128i {
+    _mm_or_si128(
+        _mm_and_si128(x, _mm_set1_epi16(0x7F)),
+        _mm_and_si128(_mm_srli_epi16(x, 1), _mm_set1_epi16(0x3F80)),
+    )
+}
+
+/// Joins the 7-bit groups of the (up to three bytes long) varints in the `u32` lanes of `x`.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+unsafe fn join_u32(x: __m128i) -> __m128i {
+    _mm_or_si128(
+        _mm_or_si128(
+            _mm_and_si128(x, _mm_set1_epi32(0x7F)),
+            _mm_and_si128(_mm_srli_epi32(x, 1), _mm_set1_epi32(0x3F80)),
+        ),
+        _mm_and_si128(_mm_srli_epi32(x, 2), _mm_set1_epi32(0x1F_C000)),
+    )
+}
+
+/// Joins the 7-bit groups of the (up to five bytes long) varints in the `u64` lanes of `x`.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+unsafe fn join_u64(x: __m128i) -> __m128i {
+    let low = _mm_or_si128(
+        _mm_or_si128(
+            _mm_and_si128(x, _mm_set1_epi64x(0x7F)),
+            _mm_and_si128(_mm_srli_epi64(x, 1), _mm_set1_epi64x(0x3F80)),
+        ),
+        _mm_and_si128(_mm_srli_epi64(x, 2), _mm_set1_epi64x(0x1F_C000)),
+    );
+    let high = _mm_or_si128(
+        _mm_and_si128(_mm_srli_epi64(x, 3), _mm_set1_epi64x(0xFE0_0000)),
+        _mm_and_si128(_mm_srli_epi64(x, 4), _mm_set1_epi64x(0x7_F000_0000)),
+    );
+    _mm_or_si128(low, high)
+}
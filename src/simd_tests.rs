@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::error::DecodeError;
+    use crate::simd::{decode_dispatch, decode_var_slice_u32, decode_var_slice_u64, BulkVarInt};
+    use crate::varint::{decode_var_slice_scalar, encode_var_slice, VarInt};
+
+    #[cfg(target_arch = "x86_64")]
+    use crate::simd::decode_ssse3;
+    #[cfg(target_arch = "x86_64")]
+    use crate::stream_vbyte::has_ssse3;
+
+    /// Values of all encoded lengths, in an order that makes varints straddle block boundaries.
+    fn mixed_u64s() -> Vec<u64> {
+        (0..2000_u64)
+            .map(|i| i.wrapping_mul(0x9E3779B97F4A7C15) >> (i % 65).min(63))
+            .collect()
+    }
+
+    fn test_inputs() -> Vec<Vec<u8>> {
+        let mut inputs = vec![vec![], vec![0x01], vec![0x7F; 100]];
+
+        let mut mixed = Vec::new();
+        encode_var_slice(&mixed_u64s(), &mut mixed);
+        inputs.push(mixed);
+
+        let mut small = Vec::new();
+        encode_var_slice(
+            &(0..1000_u64).map(|i| i % 300).collect::<Vec<_>>(),
+            &mut small,
+        );
+        inputs.push(small);
+
+        // Runs of equally long varints, decoded six, four or two at a time.
+        for shift in &[7, 14, 21, 28, 35] {
+            let mut same_len = Vec::new();
+            encode_var_slice(
+                &(0..100_u64)
+                    .map(|i| (1 << shift) - 1 - i)
+                    .collect::<Vec<_>>(),
+                &mut same_len,
+            );
+            inputs.push(same_len);
+        }
+
+        // Values of pseudo-random lengths, whose MSBs form all kinds of patterns.
+        let mut random = Vec::new();
+        encode_var_slice(
+            &(0..5000_u64)
+                .map(|i| {
+                    let r = i.wrapping_mul(0x9E3779B97F4A7C15);
+                    (r as u32) >> ((r >> 59) as u32)
+                })
+                .collect::<Vec<_>>(),
+            &mut random,
+        );
+        inputs.push(random);
+
+        // Overlong encodings are accepted by the scalar decoder as well.
+        let mut overlong = vec![0x01; 20];
+        overlong.extend_from_slice(&[0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]);
+        overlong.extend_from_slice(&[0x02; 20]);
+        inputs.push(overlong);
+
+        // Invalid inputs: truncated, overflowing, unterminated and out of range for u32.
+        let mut truncated = vec![0x01; 40];
+        truncated.push(0x80);
+        inputs.push(truncated);
+        let mut overflow = vec![0x01; 17];
+        overflow.extend_from_slice(&[0xFF; 9]);
+        overflow.extend_from_slice(&[0x02; 20]);
+        inputs.push(overflow);
+        inputs.push(vec![0xFF; 64]);
+        let mut too_big = vec![0x01; 3];
+        too_big.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0x7F]);
+        too_big.extend_from_slice(&[0x01; 40]);
+        inputs.push(too_big);
+
+        inputs
+    }
+
+    fn check_against_scalar<T: BulkVarInt + PartialEq + core::fmt::Debug>(
+        decode: impl Fn(&[u8], &mut Vec<T>) -> Result<(), DecodeError>,
+    ) {
+        for input in test_inputs() {
+            // Decode one varint after the other, independently of the slice decoders.
+            let mut expected = Vec::new();
+            let mut expected_result = Ok(());
+            let mut i = 0;
+            while i < input.len() {
+                match T::try_decode_var(&input[i..]) {
+                    Ok((v, size)) => {
+                        expected.push(v);
+                        i += size;
+                    }
+                    Err(e) => {
+                        expected_result = Err(e);
+                        break;
+                    }
+                }
+            }
+
+            let mut actual = Vec::new();
+            let actual_result = decode(&input, &mut actual);
+            assert_eq!(expected_result, actual_result, "{:?}", input);
+            if actual_result.is_ok() {
+                assert_eq!(expected, actual, "{:?}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scalar() {
+        check_against_scalar::<u32>(decode_var_slice_scalar);
+        check_against_scalar::<u64>(decode_var_slice_scalar);
+    }
+
+    #[test]
+    fn test_dispatch() {
+        check_against_scalar::<u32>(decode_dispatch);
+        check_against_scalar::<u64>(decode_dispatch);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_ssse3() {
+        if !has_ssse3() {
+            return;
+        }
+        check_against_scalar::<u32>(|src, dst| unsafe { decode_ssse3(src, dst) });
+        check_against_scalar::<u64>(|src, dst| unsafe { decode_ssse3(src, dst) });
+    }
+
+    #[test]
+    fn test_decode_var_slice_u64() {
+        let values = mixed_u64s();
+        let mut encoded = Vec::new();
+        encode_var_slice(&values, &mut encoded);
+
+        let mut decoded = vec![1];
+        assert_eq!(
+            decode_var_slice_u64(&encoded, &mut decoded),
+            Ok(values.len())
+        );
+        assert_eq!(decoded[0], 1);
+        assert_eq!(&decoded[1..], values.as_slice());
+
+        encoded.push(0x80);
+        assert_eq!(
+            decode_var_slice_u64(&encoded, &mut decoded),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(decoded.len(), values.len() + 1);
+    }
+
+    #[test]
+    fn test_decode_var_slice_u32() {
+        let values: Vec<u32> = (0..1000).map(|i| u32::MAX >> (i % 32)).collect();
+        let mut encoded = Vec::new();
+        encode_var_slice(&values, &mut encoded);
+
+        let mut decoded = Vec::new();
+        assert_eq!(
+            decode_var_slice_u32(&encoded, &mut decoded),
+            Ok(values.len())
+        );
+        assert_eq!(decoded, values);
+
+        let mut decoded = Vec::new();
+        assert_eq!(
+            decode_var_slice_u32(&(1_u64 << 32).encode_var_vec(), &mut decoded),
+            Err(DecodeError::OutOfRange)
+        );
+        assert!(decoded.is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+//! Stream VByte encoding of `u32`s, as described by Lemire, Kurz and Rupp in "Stream VByte:
+//! Faster Byte-Oriented Integer Compression".
+//!
+//! Like [group varints](crate::encode_group_varint), every value is encoded as 1 to 4
+//! little-endian bytes, and the byte lengths (minus one) of four values are stored in two bits
+//! each of a control byte, starting at the least significant bits. Unlike group varints, all
+//! control bytes are stored first, followed by all data bytes. This lets a decoder look up
+//! control bytes without waiting for the previous group to be decoded, and on x86_64 CPUs
+//! supporting SSSE3, decode four values at once with a single shuffle instruction.
+//!
+//! If the number of values is not a multiple of four, the unused bits of the last control byte
+//! are zero. The number of values is not part of the encoding and must be passed to the decoder.
+
+use alloc::vec::Vec;
+
+use crate::error::DecodeError;
+use crate::group_varint::byte_len;
+
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Returns the number of data bytes of the four values described by `control`.
+const fn group_len(control: u8) -> usize {
+    4 + (control & 0b11) as usize
+        + ((control >> 2) & 0b11) as usize
+        + ((control >> 4) & 0b11) as usize
+        + (control >> 6) as usize
+}
+
+/// The number of data bytes for each control byte.
+const GROUP_LENS: [u8; 256] = {
+    let mut lens = [0; 256];
+    let mut c = 0;
+    while c < 256 {
+        lens[c] = group_len(c as u8) as u8;
+        c += 1;
+    }
+    lens
+};
+
+/// Returns the number of control bytes needed for `count` values.
+#[inline]
+fn control_len(count: usize) -> usize {
+    count.div_ceil(4)
+}
+
+/// Returns the number of data bytes of the first `count` values described by `control`.
+fn data_len(control: &[u8], count: usize) -> usize {
+    let full = count / 4;
+    let mut len: usize = control[..full]
+        .iter()
+        .map(|&c| GROUP_LENS[c as usize] as usize)
+        .sum();
+    for k in 0..count % 4 {
+        len += ((control[full] >> (2 * k)) & 0b11) as usize + 1;
+    }
+    len
+}
+
+/// Returns the number of bytes needed to encode `src` using Stream VByte.
+pub fn stream_vbyte_required_space(src: &[u32]) -> usize {
+    control_len(src.len()) + src.iter().map(|&v| byte_len(v)).sum::<usize>()
+}
+
+/// Encodes all values in `src` using Stream VByte and appends them to `dst`. Returns the number
+/// of bytes appended.
+pub fn encode_stream_vbyte(src: &[u32], dst: &mut Vec<u8>) -> usize {
+    let start = dst.len();
+    let data_start = start + control_len(src.len());
+    // Every value is written as four bytes, so reserve the worst case and truncate afterwards.
+    dst.resize(data_start + 4 * src.len(), 0);
+
+    let mut d = data_start;
+    for (k, &v) in src.iter().enumerate() {
+        let len = byte_len(v);
+        dst[start + k / 4] |= ((len - 1) as u8) << (2 * (k % 4));
+        dst[d..d + 4].copy_from_slice(&v.to_le_bytes());
+        d += len;
+    }
+    dst.truncate(d);
+    d - start
+}
+
+/// Decodes `count` values encoded using Stream VByte from `src`, appending them to `dst`. Returns
+/// the number of bytes read from `src`; any bytes after the last value are ignored.
+///
+/// If `src` is too short to hold `count` values, [`DecodeError::UnexpectedEnd`] is returned and
+/// `dst` is left unchanged. On x86_64 CPUs supporting SSSE3, an accelerated decoder is used.
+pub fn decode_stream_vbyte(
+    src: &[u8],
+    count: usize,
+    dst: &mut Vec<u32>,
+) -> Result<usize, DecodeError> {
+    let (control, data) = split(src, count)?;
+    let used = decode_dispatch(control, data, count, dst);
+    debug_assert_eq!(used, data.len());
+    Ok(control.len() + used)
+}
+
+/// Splits `src` into the control and data bytes of `count` values, checking that all of them are
+/// present.
+pub(crate) fn split(src: &[u8], count: usize) -> Result<(&[u8], &[u8]), DecodeError> {
+    let control = src
+        .get(..control_len(count))
+        .ok_or(DecodeError::UnexpectedEnd)?;
+    let data = src[control.len()..]
+        .get(..data_len(control, count))
+        .ok_or(DecodeError::UnexpectedEnd)?;
+    Ok((control, data))
+}
+
+fn decode_dispatch(control: &[u8], data: &[u8], count: usize, dst: &mut Vec<u32>) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if has_ssse3() {
+            // Safety: SSSE3 support was checked above.
+            return unsafe { decode_ssse3(control, data, count, dst) };
+        }
+    }
+    decode_scalar(control, data, count, dst)
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn has_ssse3() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("ssse3")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "ssse3")
+    }
+}
+
+/// Decodes `count` values, appending them to `dst`. Returns the number of data bytes read.
+///
+/// `control` and `data` must hold (at least) the control and data bytes of `count` values, as
+/// returned by `split()`.
+pub(crate) fn decode_scalar(
+    control: &[u8],
+    data: &[u8],
+    count: usize,
+    dst: &mut Vec<u32>,
+) -> usize {
+    dst.reserve(count);
+    let mut d = 0;
+    for k in 0..count {
+        let len = ((control[k / 4] >> (2 * (k % 4))) & 0b11) as usize + 1;
+        let mut buf = [0_u8; 4];
+        buf[..len].copy_from_slice(&data[d..d + len]);
+        dst.push(u32::from_le_bytes(buf));
+        d += len;
+    }
+    d
+}
+
+/// `pshufb` masks moving the data bytes of a group into four `u32` lanes, for each control byte.
+/// Lane bytes not belonging to a value are zeroed (by an index with the MSB set).
+#[cfg(target_arch = "x86_64")]
+static SHUFFLES: [[u8; 16]; 256] = {
+    let mut shuffles = [[0x80; 16]; 256];
+    let mut c = 0;
+    while c < 256 {
+        let mut offset = 0;
+        let mut k = 0;
+        while k < 4 {
+            let len = ((c >> (2 * k)) & 0b11) + 1;
+            let mut j = 0;
+            while j < len {
+                shuffles[c][4 * k + j] = (offset + j) as u8;
+                j += 1;
+            }
+            offset += len;
+            k += 1;
+        }
+        c += 1;
+    }
+    shuffles
+};
+
+/// Like `decode_scalar()`, but decodes full groups with a single shuffle as long as 16 data bytes
+/// can be loaded; the remaining values are handed to `decode_scalar()`.
+///
+/// # Safety
+///
+/// The CPU must support SSSE3.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+pub(crate) unsafe fn decode_ssse3(
+    control: &[u8],
+    data: &[u8],
+    count: usize,
+    dst: &mut Vec<u32>,
+) -> usize {
+    dst.reserve(count);
+    let out = dst.as_mut_ptr().add(dst.len());
+
+    let full = count / 4;
+    let mut g = 0;
+    let mut d = 0;
+    while g < full && d + 16 <= data.len() {
+        let c = control[g] as usize;
+        let bytes = _mm_loadu_si128(data.as_ptr().add(d) as *const __m128i);
+        let shuffle = _mm_loadu_si128(SHUFFLES[c].as_ptr() as *const __m128i);
+        // `out` has capacity for `count` values, of which the first `4 * g` have been written.
+        _mm_storeu_si128(
+            out.add(4 * g) as *mut __m128i,
+            _mm_shuffle_epi8(bytes, shuffle),
+        );
+        d += GROUP_LENS[c] as usize;
+        g += 1;
+    }
+    dst.set_len(dst.len() + 4 * g);
+
+    d + decode_scalar(&control[g..], &data[d..], count - 4 * g, dst)
+}
@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::error::DecodeError;
+    use crate::stream_vbyte::{
+        decode_scalar, decode_stream_vbyte, encode_stream_vbyte, split, stream_vbyte_required_space,
+    };
+
+    #[cfg(target_arch = "x86_64")]
+    use crate::stream_vbyte::{decode_ssse3, has_ssse3};
+
+    #[test]
+    fn test_encode() {
+        let mut buf = vec![];
+        assert_eq!(
+            encode_stream_vbyte(&[1, 300, 70000, 0x1234_5678, 256], &mut buf),
+            14
+        );
+        assert_eq!(
+            buf,
+            vec![
+                0b11_10_01_00,
+                0b01,
+                1,
+                0x2C,
+                0x01,
+                0x70,
+                0x11,
+                0x01,
+                0x78,
+                0x56,
+                0x34,
+                0x12,
+                0x00,
+                0x01
+            ]
+        );
+
+        buf.clear();
+        assert_eq!(encode_stream_vbyte(&[], &mut buf), 0);
+        assert!(buf.is_empty());
+    }
+
+    /// `count` values cycling through the four encoded lengths, so that the lengths in each
+    /// control byte are 1, 2, 3 and 4 bytes.
+    fn cycle_lens(count: usize) -> Vec<u32> {
+        [0x12, 0x1234, 0x12_3456, 0x1234_5678]
+            .iter()
+            .copied()
+            .cycle()
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn test_control_and_data_streams() {
+        let values = cycle_lens(10);
+        let mut encoded = vec![];
+        assert_eq!(encode_stream_vbyte(&values, &mut encoded), 3 + 23);
+
+        // All control bytes come first; the two unused lengths of the last one are zero.
+        let (control, data) = split(&encoded, values.len()).unwrap();
+        assert_eq!(control, &[0b11_10_01_00, 0b11_10_01_00, 0b00_00_01_00]);
+        assert_eq!(data.len(), 23);
+        assert_eq!(
+            &data[..10],
+            &[0x12, 0x34, 0x12, 0x56, 0x34, 0x12, 0x78, 0x56, 0x34, 0x12]
+        );
+        assert_eq!(&data[20..], &[0x12, 0x34, 0x12]);
+    }
+
+    #[test]
+    fn test_roundtrip_counts() {
+        // Counts that are not a multiple of four leave a partial group at the end.
+        for count in (0..=9).chain(1001..=1004) {
+            for values in &[cycle_lens(count), vec![u32::MAX; count], vec![0; count]] {
+                // Appends to existing contents.
+                let mut encoded = vec![0xAA];
+                let size = encode_stream_vbyte(values, &mut encoded);
+                assert_eq!(size, stream_vbyte_required_space(values));
+                assert_eq!(encoded.len(), size + 1);
+
+                let mut decoded = vec![7];
+                assert_eq!(
+                    decode_stream_vbyte(&encoded[1..], count, &mut decoded),
+                    Ok(size)
+                );
+                assert_eq!(decoded[0], 7);
+                assert_eq!(&decoded[1..], values.as_slice());
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_trailing_bytes() {
+        let mut encoded = vec![];
+        encode_stream_vbyte(&[1, 2, 3], &mut encoded);
+        encoded.extend_from_slice(&[0xFF; 20]);
+
+        let mut decoded = vec![];
+        assert_eq!(decode_stream_vbyte(&encoded, 3, &mut decoded), Ok(4));
+        assert_eq!(decoded, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        let mut encoded = vec![];
+        encode_stream_vbyte(&cycle_lens(10), &mut encoded);
+
+        // Truncated in the control stream, and in the data stream.
+        for end in 0..encoded.len() {
+            let mut decoded = vec![1, 2];
+            assert_eq!(
+                decode_stream_vbyte(&encoded[..end], 10, &mut decoded),
+                Err(DecodeError::UnexpectedEnd)
+            );
+            assert_eq!(decoded, vec![1, 2]);
+        }
+
+        // Complete control bytes announcing more data than there is.
+        let mut decoded = vec![];
+        assert_eq!(
+            decode_stream_vbyte(&[0xFF, 0, 0, 0, 0], 1, &mut decoded),
+            Ok(5)
+        );
+        assert_eq!(
+            decode_stream_vbyte(&[0xFF, 0, 0, 0], 1, &mut decoded),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(decoded, vec![0]);
+
+        // Asking for more values than were encoded needs another control byte.
+        let mut decoded = vec![];
+        assert_eq!(
+            decode_stream_vbyte(&encoded, 13, &mut decoded),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert!(decoded.is_empty());
+    }
+
+    fn check_against_scalar(decode: impl Fn(&[u8], &[u8], usize, &mut Vec<u32>) -> usize) {
+        // Arbitrary bytes are valid, as long as there are enough of them.
+        let mut state = 0x2545_F491_u32;
+        let noise: Vec<u8> = (0..600)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+
+        for count in 0..=100 {
+            for input in &[&noise[..], &[0xFF; 500][..], &[0; 500][..]] {
+                let (control, data) = split(input, count).unwrap();
+
+                let mut expected = vec![];
+                let expected_len = decode_scalar(control, data, count, &mut expected);
+                assert_eq!(expected_len, data.len());
+                assert_eq!(expected.len(), count);
+
+                let mut actual = vec![42];
+                assert_eq!(decode(control, data, count, &mut actual), expected_len);
+                assert_eq!(actual[0], 42);
+                assert_eq!(&actual[1..], expected.as_slice(), "count {}", count);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scalar() {
+        check_against_scalar(decode_scalar);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_ssse3() {
+        if !has_ssse3() {
+            return;
+        }
+        check_against_scalar(|control, data, count, dst| unsafe {
+            decode_ssse3(control, data, count, dst)
+        });
+    }
+}
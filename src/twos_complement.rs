@@ -0,0 +1,66 @@
+use crate::error::DecodeError;
+use crate::varint::VarInt;
+
+/// A signed integer encoded as varint of its two's complement representation, without zigzag
+/// encoding, as protobuf does for `int32` and `int64` fields.
+///
+/// Values are sign-extended to 64 bits before being encoded, so negative values always take 10
+/// bytes. When decoding a `TwosComplement<i32>`, the decoded 64 bit value is truncated to its
+/// lower 32 bits, like protobuf parsers do; this also accepts the 5 byte encodings of negative
+/// values written by some older implementations.
+///
+/// Use unsigned integers for protobuf `uint32`/`uint64` fields, and signed integers (which are
+/// zigzag-encoded) for `sint32`/`sint64` fields.
+///
+/// Since `TwosComplement` implements [`VarInt`], it can be used anywhere a varint is expected,
+/// e.g. with [`VarIntReader`](crate::VarIntReader) and [`VarIntWriter`](crate::VarIntWriter)
+/// or for packed repeated fields.
+///
+/// ```
+/// use integer_encoding::{TwosComplement, VarInt};
+///
+/// let mut buf = [0_u8; 10];
+/// assert_eq!(TwosComplement(-1_i32).encode_var(&mut buf), 10);
+/// assert_eq!(buf, [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]);
+/// assert_eq!(TwosComplement::<i32>::decode_var(&buf), Some((TwosComplement(-1), 10)));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TwosComplement<T>(pub T);
+
+macro_rules! impl_twos_complement {
+    ($t:ty) => {
+        impl VarInt for TwosComplement<$t> {
+            fn required_space(self) -> usize {
+                (self.0 as u64).required_space()
+            }
+
+            fn try_decode_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let (n, s) = u64::try_decode_var(src)?;
+                Ok((TwosComplement(n as $t), s))
+            }
+
+            fn encode_var(self, dst: &mut [u8]) -> usize {
+                (self.0 as u64).encode_var(dst)
+            }
+
+            fn varint_max_size() -> usize {
+                u64::varint_max_size()
+            }
+        }
+
+        impl From<$t> for TwosComplement<$t> {
+            fn from(v: $t) -> Self {
+                TwosComplement(v)
+            }
+        }
+
+        impl From<TwosComplement<$t>> for $t {
+            fn from(v: TwosComplement<$t>) -> Self {
+                v.0
+            }
+        }
+    };
+}
+
+impl_twos_complement!(i32);
+impl_twos_complement!(i64);
@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    #[cfg(feature = "std")]
+    use std::io;
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::reader::VarIntAsyncReader;
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::writer::VarIntAsyncWriter;
+
+    use crate::error::DecodeError;
+    #[cfg(feature = "alloc")]
+    use crate::packed::{decode_packed_varint, encode_packed_varint};
+    use crate::processor::VarIntProcessor;
+    #[cfg(feature = "std")]
+    use crate::reader::VarIntReader;
+    use crate::twos_complement::TwosComplement;
+    use crate::varint::VarInt;
+    #[cfg(feature = "std")]
+    use crate::writer::VarIntWriter;
+
+    const MINUS_ONE: [u8; 10] = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode() {
+        assert_eq!(TwosComplement(0_i32).encode_var_vec(), vec![0]);
+        assert_eq!(TwosComplement(1_i32).encode_var_vec(), vec![1]);
+        assert_eq!(TwosComplement(150_i64).encode_var_vec(), vec![0x96, 0x01]);
+        assert_eq!(TwosComplement(-1_i32).encode_var_vec(), MINUS_ONE.to_vec());
+        assert_eq!(TwosComplement(-1_i64).encode_var_vec(), MINUS_ONE.to_vec());
+        assert_eq!(
+            TwosComplement(i32::MAX).encode_var_vec(),
+            vec![0xFF, 0xFF, 0xFF, 0xFF, 0x07]
+        );
+        assert_eq!(
+            TwosComplement(i32::MIN).encode_var_vec(),
+            vec![0x80, 0x80, 0x80, 0x80, 0xF8, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]
+        );
+        assert_eq!(
+            TwosComplement(i64::MIN).encode_var_vec(),
+            vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01]
+        );
+
+        assert_eq!(TwosComplement(-1_i32).required_space(), 10);
+        assert_eq!(TwosComplement(i32::MAX).required_space(), 5);
+        assert_eq!(TwosComplement(i64::MAX).required_space(), 9);
+        assert_eq!(TwosComplement::<i32>::varint_max_size(), 10);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for &v in &[0, 1, -1, 63, -64, 300, -300, i32::MAX, i32::MIN] {
+            let mut buf = [0_u8; 10];
+            let len = TwosComplement(v).encode_var(&mut buf);
+            assert_eq!(
+                TwosComplement::<i32>::decode_var(&buf[..len]),
+                Some((TwosComplement(v), len))
+            );
+        }
+        for &v in &[0, 1, -1, 1 << 40, -(1 << 40), i64::MAX, i64::MIN] {
+            let mut buf = [0_u8; 10];
+            let len = TwosComplement(v).encode_var(&mut buf);
+            assert_eq!(
+                TwosComplement::<i64>::decode_var(&buf[..len]),
+                Some((TwosComplement(v), len))
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_int32_truncates() {
+        // Some implementations write negative int32 values without sign extension.
+        assert_eq!(
+            TwosComplement::<i32>::decode_var(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+            Some((TwosComplement(-1), 5))
+        );
+        // Bits beyond the lower 32 are dropped, as in protobuf.
+        let mut buf = [0_u8; 10];
+        let len = ((1_u64 << 32) + 5).encode_var(&mut buf);
+        assert_eq!(
+            TwosComplement::<i32>::decode_var(&buf[..len]),
+            Some((TwosComplement(5), len))
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            TwosComplement::<i32>::try_decode_var(&MINUS_ONE[..9]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        let mut overflow = MINUS_ONE;
+        overflow[9] = 0x02;
+        assert_eq!(
+            TwosComplement::<i64>::try_decode_var(&overflow),
+            Err(DecodeError::Overflow)
+        );
+        assert_eq!(
+            TwosComplement::<i64>::try_decode_var_canonical(&[0x81, 0x00]),
+            Err(DecodeError::NonCanonical)
+        );
+    }
+
+    #[test]
+    fn test_decode_canonical() {
+        // Values truncated to 32 bits only have their shortest encoding accepted.
+        assert_eq!(
+            TwosComplement::<i32>::try_decode_var_canonical(&[0x85, 0x80, 0x80, 0x80, 0x10]),
+            Err(DecodeError::NonCanonical)
+        );
+        assert_eq!(
+            TwosComplement::<i32>::try_decode_var_canonical(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+            Err(DecodeError::NonCanonical)
+        );
+        assert_eq!(
+            TwosComplement::<i32>::decode_var_canonical(&[0x05]),
+            Some((TwosComplement(5), 1))
+        );
+        assert_eq!(
+            TwosComplement::<i32>::decode_var_canonical(&MINUS_ONE),
+            Some((TwosComplement(-1), 10))
+        );
+    }
+
+    #[test]
+    fn test_processor() {
+        let mut p = VarIntProcessor::<TwosComplement<i32>>::new();
+        assert_eq!(p.feed(&MINUS_ONE[..4]), Ok((4, None)));
+        assert_eq!(p.feed(&MINUS_ONE[4..]), Ok((6, Some(TwosComplement(-1)))));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_writer() {
+        let mut buf = vec![];
+        assert_eq!(buf.write_varint(TwosComplement(-2_i32)).unwrap(), 10);
+        buf.write_varint(TwosComplement(300_i64)).unwrap();
+
+        let mut r: &[u8] = &buf;
+        assert_eq!(
+            r.read_varint::<TwosComplement<i32>>().unwrap(),
+            TwosComplement(-2)
+        );
+        assert_eq!(
+            r.read_varint::<TwosComplement<i64>>().unwrap(),
+            TwosComplement(300)
+        );
+        assert_eq!(
+            r.read_varint::<TwosComplement<i32>>().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_packed() {
+        let values = [TwosComplement(1_i32), TwosComplement(-1), TwosComplement(2)];
+        let mut buf = vec![];
+        assert_eq!(encode_packed_varint(values.iter().copied(), &mut buf), 13);
+        assert_eq!(buf[0], 12);
+
+        let mut decoded: Vec<TwosComplement<i32>> = vec![];
+        assert_eq!(decode_packed_varint(&buf, &mut decoded), Ok(13));
+        assert_eq!(decoded, values);
+    }
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    #[tokio::test]
+    async fn test_async_reader_writer() {
+        let mut buf = vec![];
+        buf.write_varint_async(TwosComplement(i64::MIN))
+            .await
+            .unwrap();
+        assert_eq!(buf.len(), 10);
+
+        let mut r: &[u8] = &buf;
+        assert_eq!(
+            r.read_varint_async::<TwosComplement<i64>>().await.unwrap(),
+            TwosComplement(i64::MIN)
+        );
+    }
+}
@@ -1,7 +1,14 @@
-use std::mem::size_of;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::error::DecodeError;
+use crate::iter::VarIntIter;
+use crate::zigzag::ZigZag;
 
 /// Most-significant byte, == 0x80
 pub const MSB: u8 = 0b1000_0000;
+/// The longest possible encoded varint (that of a `u128`/`i128`), in bytes.
+pub(crate) const MAX_VARINT_SIZE: usize = 19;
 /// All bits except for the most significant. Can be used as bitmask to drop the most-significant
 /// bit using `&` (binary-and).
 const DROP_MSB: u8 = 0b0111_1111;
@@ -24,13 +31,28 @@ fn required_encoded_space_unsigned(mut v: u64) -> usize {
 /// How many bytes an integer uses when being encoded as a [`VarInt`].
 #[inline]
 fn required_encoded_space_signed(v: i64) -> usize {
-    required_encoded_space_unsigned(zigzag_encode(v))
+    required_encoded_space_unsigned(v.zigzag_encode())
+}
+
+/// Like `required_encoded_space_unsigned()`, but for 128 bit integers.
+#[inline]
+fn required_encoded_space_unsigned_128(mut v: u128) -> usize {
+    if v == 0 {
+        return 1;
+    }
+
+    let mut logcounter = 0;
+    while v > 0 {
+        logcounter += 1;
+        v >>= 7;
+    }
+    logcounter
 }
 
 /// Varint (variable length integer) encoding, as described in
 /// <https://developers.google.com/protocol-buffers/docs/encoding>.
 ///
-/// Uses zigzag encoding (also described there) for signed integer representation.
+/// Uses zigzag encoding (also described there, see [`ZigZag`]) for signed integer representation.
 pub trait VarInt: Sized + Copy {
     /// Returns the number of bytes this number needs in its encoded form. Note: This varies
     /// depending on the actual number you want to encode.
@@ -38,13 +60,50 @@ pub trait VarInt: Sized + Copy {
     /// Decode a value from the slice. Returns the value and the number of bytes read from the
     /// slice (can be used to read several consecutive values from a big slice)
     /// return None if all bytes has MSB set.
-    fn decode_var(src: &[u8]) -> Option<(Self, usize)>;
+    ///
+    /// Use `try_decode_var()` to find out why decoding failed.
+    fn decode_var(src: &[u8]) -> Option<(Self, usize)> {
+        Self::try_decode_var(src).ok()
+    }
+    /// Like `decode_var()`, but returns a [`DecodeError`] describing why a value could not be
+    /// decoded.
+    fn try_decode_var(src: &[u8]) -> Result<(Self, usize), DecodeError>;
+
+    /// Like `decode_var()`, but only accepts the shortest possible encoding of a value. This
+    /// guarantees that every value has exactly one valid encoding, e.g. `[0x80, 0x00]` is
+    /// rejected as encoding of zero.
+    fn decode_var_canonical(src: &[u8]) -> Option<(Self, usize)> {
+        Self::try_decode_var_canonical(src).ok()
+    }
+    /// Like `decode_var_canonical()`, but returns a [`DecodeError`] describing why a value could
+    /// not be decoded. Overlong encodings result in [`DecodeError::NonCanonical`]; values whose
+    /// last byte carries bits beyond the target type's width result in
+    /// [`DecodeError::Overflow`] or [`DecodeError::OutOfRange`], as with `try_decode_var()`.
+    fn try_decode_var_canonical(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (v, size) = Self::try_decode_var(src)?;
+        if size != v.required_space() {
+            return Err(DecodeError::NonCanonical);
+        }
+        Ok((v, size))
+    }
     /// Encode a value into the slice. The slice must be at least `required_space()` bytes long.
     /// The number of bytes taken by the encoded integer is returned.
     fn encode_var(self, src: &mut [u8]) -> usize;
 
+    /// Returns the largest number of bytes any value of this type needs in its encoded form.
+    fn varint_max_size() -> usize {
+        (core::mem::size_of::<Self>() * 8 + 7) / 7
+    }
+
+    /// Returns an iterator over the varints in `src`, which are decoded lazily and without
+    /// copying. See [`VarIntIter`].
+    fn iter_var(src: &[u8]) -> VarIntIter<'_, Self> {
+        VarIntIter::new(src)
+    }
+
     /// Helper: Encode a value and return the encoded form as Vec. The Vec must be at least
     /// `required_space()` bytes long.
+    #[cfg(feature = "alloc")]
     fn encode_var_vec(self) -> Vec<u8> {
         let mut v = vec![0; self.required_space()];
         self.encode_var(&mut v);
@@ -52,27 +111,102 @@ pub trait VarInt: Sized + Copy {
     }
 }
 
-#[inline]
-fn zigzag_encode(from: i64) -> u64 {
-    ((from << 1) ^ (from >> 63)) as u64
+/// Returns the number of bytes needed to encode all values in `src` as consecutive varints.
+pub fn required_space_slice<VI: VarInt>(src: &[VI]) -> usize {
+    src.iter().map(|v| v.required_space()).sum()
 }
 
-// see: http://stackoverflow.com/a/2211086/56332
-// casting required because operations like unary negation
-// cannot be performed on unsigned integers
-#[inline]
-fn zigzag_decode(from: u64) -> i64 {
-    ((from >> 1) ^ (-((from & 1) as i64)) as u64) as i64
+/// Encodes all values in `src` as consecutive varints and appends them to `dst`.
+///
+/// This is faster than calling `encode_var_vec()` for each value, as `dst` is grown at most once.
+/// Returns the number of bytes appended.
+#[cfg(feature = "alloc")]
+pub fn encode_var_slice<VI: VarInt>(src: &[VI], dst: &mut Vec<u8>) -> usize {
+    let start = dst.len();
+    dst.resize(start + src.len() * VI::varint_max_size(), 0);
+
+    let mut i = start;
+    for v in src {
+        i += v.encode_var(&mut dst[i..]);
+    }
+    dst.truncate(i);
+    i - start
+}
+
+/// Decodes consecutive varints from `src` until it is exhausted, appending them to `dst`. Returns
+/// the number of values decoded.
+///
+/// If any varint fails to decode, `dst` is restored to its previous length and the error is
+/// returned. For `u32` and `u64`, this uses SSSE3 if the CPU supports it, which is faster than
+/// decoding one value at a time as long as most varints are at most five bytes long.
+///
+/// This is implemented for all integer types and [`TwosComplement`](crate::TwosComplement).
+#[cfg(feature = "alloc")]
+pub fn decode_var_slice<VI: slice::DecodeVarSlice>(
+    src: &[u8],
+    dst: &mut Vec<VI>,
+) -> Result<usize, DecodeError> {
+    let start = dst.len();
+    match VI::decode_var_slice_into(src, dst) {
+        Ok(()) => Ok(dst.len() - start),
+        Err(e) => {
+            dst.truncate(start);
+            Err(e)
+        }
+    }
 }
 
-pub(crate) trait VarIntMaxSize {
-    fn varint_max_size() -> usize;
+/// Decodes one varint after the other; the fallback of `decode_var_slice()`.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_var_slice_scalar<VI: VarInt>(
+    src: &[u8],
+    dst: &mut Vec<VI>,
+) -> Result<(), DecodeError> {
+    let mut i = 0;
+    while i < src.len() {
+        let (v, size) = VI::try_decode_var(&src[i..])?;
+        dst.push(v);
+        i += size;
+    }
+    Ok(())
 }
 
-impl<VI: VarInt> VarIntMaxSize for VI {
-    fn varint_max_size() -> usize {
-        (size_of::<VI>() * 8 + 7) / 7
+#[cfg(feature = "alloc")]
+pub(crate) mod slice {
+    use alloc::vec::Vec;
+
+    use super::{decode_var_slice_scalar, VarInt};
+    use crate::error::DecodeError;
+    use crate::twos_complement::TwosComplement;
+
+    /// Chooses the decoder used by `decode_var_slice()`. As this module is private, the trait
+    /// cannot be implemented outside of this crate.
+    pub trait DecodeVarSlice: VarInt {
+        /// Appends the consecutive varints in `src` to `dst`, stopping at the first error.
+        fn decode_var_slice_into(src: &[u8], dst: &mut Vec<Self>) -> Result<(), DecodeError>;
     }
+
+    macro_rules! impl_decode_var_slice {
+        ($decode:path; $($t:ty),*) => {
+            $(
+                impl DecodeVarSlice for $t {
+                    fn decode_var_slice_into(
+                        src: &[u8],
+                        dst: &mut Vec<Self>,
+                    ) -> Result<(), DecodeError> {
+                        $decode(src, dst)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_decode_var_slice!(crate::simd::decode_dispatch; u32, u64);
+    impl_decode_var_slice!(
+        decode_var_slice_scalar;
+        u8, u16, u128, usize, i8, i16, i32, i64, i128, isize,
+        TwosComplement<i32>, TwosComplement<i64>
+    );
 }
 
 macro_rules! impl_varint {
@@ -82,9 +216,11 @@ macro_rules! impl_varint {
                 required_encoded_space_unsigned(self as u64)
             }
 
-            fn decode_var(src: &[u8]) -> Option<(Self, usize)> {
-                let (n, s) = u64::decode_var(src)?;
-                Some((<Self as std::convert::TryFrom<u64>>::try_from(n).ok()?, s))
+            fn try_decode_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let (n, s) = u64::try_decode_var(src)?;
+                let n = <Self as core::convert::TryFrom<u64>>::try_from(n)
+                    .map_err(|_| DecodeError::OutOfRange)?;
+                Ok((n, s))
             }
 
             fn encode_var(self, dst: &mut [u8]) -> usize {
@@ -98,9 +234,11 @@ macro_rules! impl_varint {
                 required_encoded_space_signed(self as i64)
             }
 
-            fn decode_var(src: &[u8]) -> Option<(Self, usize)> {
-                let (n, s) = i64::decode_var(src)?;
-                Some((<Self as std::convert::TryFrom<i64>>::try_from(n).ok()?, s))
+            fn try_decode_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let (n, s) = i64::try_decode_var(src)?;
+                let n = <Self as core::convert::TryFrom<i64>>::try_from(n)
+                    .map_err(|_| DecodeError::OutOfRange)?;
+                Ok((n, s))
             }
 
             fn encode_var(self, dst: &mut [u8]) -> usize {
@@ -129,30 +267,27 @@ impl VarInt for u64 {
     }
 
     #[inline]
-    fn decode_var(src: &[u8]) -> Option<(Self, usize)> {
+    fn try_decode_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
         let mut result: u64 = 0;
         let mut shift = 0;
 
-        let mut success = false;
         for b in src {
             let msb_dropped = b & DROP_MSB;
             result |= u64::from(msb_dropped) << shift;
             shift += 7;
 
             if shift > (9 * 7) {
-                success = *b < 2;
-                break;
+                return if *b < 2 {
+                    Ok((result, shift / 7))
+                } else {
+                    Err(DecodeError::Overflow)
+                };
             } else if b & MSB == 0 {
-                success = true;
-                break;
+                return Ok((result, shift / 7));
             }
         }
 
-        if success {
-            Some((result, shift / 7))
-        } else {
-            None
-        }
+        Err(DecodeError::UnexpectedEnd)
     }
 
     #[inline]
@@ -178,18 +313,62 @@ impl VarInt for i64 {
     }
 
     #[inline]
-    fn decode_var(src: &[u8]) -> Option<(Self, usize)> {
-        if let Some((result, size)) = u64::decode_var(src) {
-            Some((zigzag_decode(result), size))
-        } else {
-            None
+    fn try_decode_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (result, size) = u64::try_decode_var(src)?;
+        Ok((i64::zigzag_decode(result), size))
+    }
+
+    #[inline]
+    fn encode_var(self, dst: &mut [u8]) -> usize {
+        debug_assert!(dst.len() >= self.required_space());
+        let mut n: u64 = self.zigzag_encode();
+        let mut i = 0;
+
+        while n >= 0x80 {
+            dst[i] = MSB | (n as u8);
+            i += 1;
+            n >>= 7;
+        }
+
+        dst[i] = n as u8;
+        i + 1
+    }
+}
+
+impl VarInt for u128 {
+    fn required_space(self) -> usize {
+        required_encoded_space_unsigned_128(self)
+    }
+
+    #[inline]
+    fn try_decode_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let mut result: u128 = 0;
+        let mut shift = 0;
+
+        for b in src {
+            let msb_dropped = b & DROP_MSB;
+            result |= u128::from(msb_dropped) << shift;
+            shift += 7;
+
+            if shift > (18 * 7) {
+                // Only two bits are left for the 19th byte.
+                return if *b < 4 {
+                    Ok((result, shift / 7))
+                } else {
+                    Err(DecodeError::Overflow)
+                };
+            } else if b & MSB == 0 {
+                return Ok((result, shift / 7));
+            }
         }
+
+        Err(DecodeError::UnexpectedEnd)
     }
 
     #[inline]
     fn encode_var(self, dst: &mut [u8]) -> usize {
         debug_assert!(dst.len() >= self.required_space());
-        let mut n: u64 = zigzag_encode(self);
+        let mut n = self;
         let mut i = 0;
 
         while n >= 0x80 {
@@ -202,3 +381,20 @@ impl VarInt for i64 {
         i + 1
     }
 }
+
+impl VarInt for i128 {
+    fn required_space(self) -> usize {
+        required_encoded_space_unsigned_128(self.zigzag_encode())
+    }
+
+    #[inline]
+    fn try_decode_var(src: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (result, size) = u128::try_decode_var(src)?;
+        Ok((i128::zigzag_decode(result), size))
+    }
+
+    #[inline]
+    fn encode_var(self, dst: &mut [u8]) -> usize {
+        self.zigzag_encode().encode_var(dst)
+    }
+}
@@ -5,8 +5,19 @@ mod tests {
     #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
     use crate::writer::VarIntAsyncWriter;
 
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+
+    use crate::error::DecodeError;
+    use crate::processor::VarIntProcessor;
+    #[cfg(feature = "std")]
     use crate::reader::VarIntReader;
-    use crate::varint::VarInt;
+    #[cfg(feature = "alloc")]
+    use crate::varint::{decode_var_slice, encode_var_slice};
+    use crate::varint::{required_space_slice, VarInt};
+    #[cfg(feature = "std")]
     use crate::writer::VarIntWriter;
 
     #[test]
@@ -17,14 +28,17 @@ mod tests {
         assert_eq!(16384_u32.required_space(), 3);
         assert_eq!(2097151_u32.required_space(), 3);
         assert_eq!(2097152_u32.required_space(), 4);
+        assert_eq!(required_space_slice(&[0_u32, 128, 16384]), 6);
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_encode_u64() {
         assert_eq!(0_u32.encode_var_vec(), vec![0b00000000]);
         assert_eq!(300_u32.encode_var_vec(), vec![0b10101100, 0b00000010]);
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_identity_u64() {
         for i in 1_u64..100 {
@@ -41,6 +55,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_decode_max_u64() {
         let max_vec_encoded = vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
@@ -50,12 +65,14 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_decode_max_u64_plus_one() {
         let max_vec_encoded = vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x02];
         assert!(u64::decode_var(max_vec_encoded.as_slice()).is_none());
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_encode_i64() {
         assert_eq!(0_i64.encode_var_vec(), 0_u32.encode_var_vec());
@@ -75,6 +92,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_decode_min_i64() {
         let min_vec_encoded = vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
@@ -84,6 +102,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_decode_max_i64() {
         let max_vec_encoded = vec![0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
@@ -93,12 +112,66 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_max_u128() {
+        let mut max_vec_encoded = vec![0xFF; 18];
+        max_vec_encoded.push(0x03);
+        assert_eq!(u128::MAX.encode_var_vec(), max_vec_encoded);
+        assert_eq!(u128::MAX.required_space(), 19);
+        assert_eq!(
+            u128::decode_var(max_vec_encoded.as_slice()).unwrap(),
+            (u128::MAX, 19)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_max_u128_plus_one() {
+        let mut max_vec_encoded = vec![0x80; 18];
+        max_vec_encoded.push(0x04);
+        assert!(u128::decode_var(max_vec_encoded.as_slice()).is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_identity_128() {
+        for &i in &[0_u128, 1, 300, u64::MAX as u128 + 1, 1 << 100, u128::MAX] {
+            assert_eq!(
+                u128::decode_var(i.encode_var_vec().as_slice()).unwrap(),
+                (i, i.required_space())
+            );
+        }
+        for &i in &[0_i128, -1, 150, i64::MIN as i128 - 1, i128::MIN, i128::MAX] {
+            assert_eq!(
+                i128::decode_var(i.encode_var_vec().as_slice()).unwrap(),
+                (i, i.required_space())
+            );
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_i128() {
+        assert_eq!((-150_i128).encode_var_vec(), 299_u32.encode_var_vec());
+        assert_eq!(
+            i64::MIN.encode_var_vec(),
+            (i64::MIN as i128).encode_var_vec()
+        );
+
+        let mut min_vec_encoded = vec![0xFF; 18];
+        min_vec_encoded.push(0x03);
+        assert_eq!(i128::MIN.encode_var_vec(), min_vec_encoded);
+    }
+
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_encode_i16() {
         assert_eq!(150_i16.encode_var_vec(), 300_u32.encode_var_vec());
         assert_eq!((-150_i16).encode_var_vec(), 299_u32.encode_var_vec());
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_reader_writer() {
         let mut buf = Vec::with_capacity(128);
@@ -108,12 +181,16 @@ mod tests {
         let i3: u32 = 4200123456;
         let i4: i64 = i3 as i64 * 1000;
         let i5: i32 = -32456;
+        let i6: u128 = u128::MAX - 12345;
+        let i7: i128 = i128::MIN + 12345;
 
         assert!(buf.write_varint(i1).is_ok());
         assert!(buf.write_varint(i2).is_ok());
         assert!(buf.write_varint(i3).is_ok());
         assert!(buf.write_varint(i4).is_ok());
         assert!(buf.write_varint(i5).is_ok());
+        assert!(buf.write_varint(i6).is_ok());
+        assert!(buf.write_varint(i7).is_ok());
 
         let mut reader: &[u8] = buf.as_ref();
 
@@ -122,6 +199,8 @@ mod tests {
         assert_eq!(i3, reader.read_varint().unwrap());
         assert_eq!(i4, reader.read_varint().unwrap());
         assert_eq!(i5, reader.read_varint().unwrap());
+        assert_eq!(i6, reader.read_varint().unwrap());
+        assert_eq!(i7, reader.read_varint().unwrap());
 
         assert!(reader.read_varint::<u32>().is_err());
     }
@@ -136,12 +215,14 @@ mod tests {
         let i3: u32 = 4200123456;
         let i4: i64 = i3 as i64 * 1000;
         let i5: i32 = -32456;
+        let i6: u128 = u128::MAX - 12345;
 
         buf.write_varint_async(i1).await.unwrap();
         buf.write_varint_async(i2).await.unwrap();
         buf.write_varint_async(i3).await.unwrap();
         buf.write_varint_async(i4).await.unwrap();
         buf.write_varint_async(i5).await.unwrap();
+        buf.write_varint_async(i6).await.unwrap();
 
         let mut reader: &[u8] = buf.as_ref();
 
@@ -150,9 +231,11 @@ mod tests {
         assert_eq!(i3, reader.read_varint_async().await.unwrap());
         assert_eq!(i4, reader.read_varint_async().await.unwrap());
         assert_eq!(i5, reader.read_varint_async().await.unwrap());
+        assert_eq!(i6, reader.read_varint_async().await.unwrap());
         assert!(reader.read_varint_async::<u32>().await.is_err());
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_unterminated_varint() {
         let buf = vec![0xff_u8; 12];
@@ -160,6 +243,15 @@ mod tests {
         assert!(read.read_varint::<u64>().is_err());
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_unterminated_varint_u128() {
+        let buf = vec![0xff_u8; 20];
+        let mut read = buf.as_slice();
+        assert!(read.read_varint::<u128>().is_err());
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn test_unterminated_varint_2() {
         let buf = [0xff, 0xff];
@@ -167,6 +259,7 @@ mod tests {
         assert!(read.read_varint::<u64>().is_err());
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_decode_extra_bytes_u64() {
         let mut encoded = 0x12345u64.encode_var_vec();
@@ -184,6 +277,7 @@ mod tests {
         assert_eq!(u64::decode_var(&encoded[..]), None);
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_decode_extra_bytes_i64() {
         let mut encoded = (-0x12345i64).encode_var_vec();
@@ -201,6 +295,7 @@ mod tests {
         assert_eq!(i64::decode_var(&encoded[..]), None);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_regression_22() {
         let encoded: Vec<u8> = 0x112233_u64.encode_var_vec();
@@ -210,6 +305,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_decode_var_too_big() {
         assert_eq!(u8::decode_var(&0x1FF_u64.encode_var_vec()), None, "u8");
@@ -227,4 +323,351 @@ mod tests {
             "i32"
         );
     }
+
+    #[test]
+    fn test_try_decode_var_errors() {
+        assert_eq!(
+            u64::try_decode_var(&[0xFF, 0xFF]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(u64::try_decode_var(&[]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(
+            u64::try_decode_var(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x02]),
+            Err(DecodeError::Overflow)
+        );
+        assert_eq!(
+            u8::try_decode_var(&[0xFF, 0x03]),
+            Err(DecodeError::OutOfRange)
+        );
+        // 0x8000, zigzag encoded.
+        assert_eq!(
+            i16::try_decode_var(&[0x80, 0x80, 0x04]),
+            Err(DecodeError::OutOfRange)
+        );
+        assert_eq!(u8::try_decode_var(&[0x7F, 0x01]), Ok((0x7F, 1)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_error_source() {
+        let err = (&[0x80_u8, 0x80][..]).read_varint::<u32>().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::UnexpectedEnd)
+        );
+
+        let encoded = 0x1FF_u32.encode_var_vec();
+        let err = encoded.as_slice().read_varint::<u8>().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_decode_var_canonical() {
+        assert_eq!(u64::decode_var(&[0x80, 0x00]), Some((0, 2)));
+        assert_eq!(u64::decode_var_canonical(&[0x80, 0x00]), None);
+        assert_eq!(
+            u32::try_decode_var_canonical(&[0xAC, 0x82, 0x00]),
+            Err(DecodeError::NonCanonical)
+        );
+        assert_eq!(
+            i64::try_decode_var_canonical(&[0x81, 0x80, 0x80, 0x00]),
+            Err(DecodeError::NonCanonical)
+        );
+        assert_eq!(u64::decode_var_canonical(&[0x00]), Some((0, 1)));
+        assert_eq!(
+            u32::decode_var_canonical(&[0xAC, 0x02, 0x00]),
+            Some((300, 2))
+        );
+
+        // Padding bits in the final byte.
+        assert_eq!(
+            u64::try_decode_var_canonical(&[
+                0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x03
+            ]),
+            Err(DecodeError::Overflow)
+        );
+        assert_eq!(
+            u32::try_decode_var_canonical(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F]),
+            Err(DecodeError::OutOfRange)
+        );
+
+        let mut buf = [0_u8; 10];
+        for &i in &[0_u64, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let len = i.encode_var(&mut buf);
+            assert_eq!(u64::decode_var_canonical(&buf[..len]), Some((i, len)));
+        }
+        for &i in &[0_i64, -1, 64, -65, i64::MIN, i64::MAX] {
+            let len = i.encode_var(&mut buf);
+            assert_eq!(i64::decode_var_canonical(&buf[..len]), Some((i, len)));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_varint_canonical() {
+        let buf = [0xAC, 0x02, 0x80, 0x00, 0x01];
+        let mut reader = &buf[..];
+        assert_eq!(reader.read_varint_canonical::<u32>().unwrap(), 300);
+
+        let err = reader.read_varint_canonical::<u32>().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::NonCanonical)
+        );
+        assert_eq!(reader.read_varint_canonical::<u32>().unwrap(), 1);
+        assert!(reader.read_varint_canonical::<u32>().is_err());
+    }
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    #[tokio::test]
+    async fn test_async_read_varint_canonical() {
+        let buf = [0xAC, 0x02, 0x80, 0x00];
+        let mut reader = &buf[..];
+        assert_eq!(
+            reader.read_varint_canonical_async::<u32>().await.unwrap(),
+            300
+        );
+        assert!(reader.read_varint_canonical_async::<u32>().await.is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_slice_roundtrip() {
+        let values: Vec<i64> = vec![0, -1, 1, 300, -300, i64::MIN, i64::MAX, 1 << 40];
+        let expected: Vec<u8> = values.iter().flat_map(|v| v.encode_var_vec()).collect();
+
+        let mut buf = vec![0xAA];
+        assert_eq!(encode_var_slice(&values, &mut buf), expected.len());
+        assert_eq!(buf[0], 0xAA);
+        assert_eq!(&buf[1..], expected.as_slice());
+        assert_eq!(required_space_slice(&values), expected.len());
+
+        let mut decoded = vec![5_i64];
+        assert_eq!(decode_var_slice(&buf[1..], &mut decoded), Ok(values.len()));
+        assert_eq!(decoded[0], 5);
+        assert_eq!(&decoded[1..], values.as_slice());
+
+        assert_eq!(encode_var_slice::<u32>(&[], &mut buf), 0);
+        assert_eq!(required_space_slice::<u32>(&[]), 0);
+        assert_eq!(decode_var_slice::<u32>(&[], &mut Vec::new()), Ok(0));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_var_slice_errors() {
+        let mut decoded = vec![1_u8];
+        assert_eq!(
+            decode_var_slice(&[0x01, 0x02, 0x80], &mut decoded),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(decoded, vec![1]);
+        assert_eq!(
+            decode_var_slice(&[0x01, 0x80, 0x02], &mut decoded),
+            Err(DecodeError::OutOfRange)
+        );
+        assert_eq!(decoded, vec![1]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_iter_var() {
+        let values = [1_i32, -300, i32::MAX, 0];
+        let mut buf = Vec::new();
+        encode_var_slice(&values, &mut buf);
+
+        let decoded: Result<Vec<i32>, _> = i32::iter_var(&buf).collect();
+        assert_eq!(decoded.unwrap(), values);
+
+        let mut it = i32::iter_var(&buf);
+        assert_eq!(it.next(), Some(Ok(1)));
+        assert_eq!(it.offset(), 1);
+        assert_eq!(it.next(), Some(Ok(-300)));
+        assert_eq!(it.offset(), 3);
+        assert_eq!(it.remaining(), &buf[3..]);
+        assert_eq!(it.by_ref().count(), 2);
+        assert_eq!(it.remaining(), &[]);
+        assert_eq!(it.next(), None);
+
+        assert_eq!(u8::iter_var(&[]).next(), None);
+    }
+
+    #[test]
+    fn test_iter_var_truncated() {
+        let buf = [0x01, 0xAC, 0x02, 0xFF, 0xFF];
+        let mut it = u32::iter_var(&buf);
+        assert_eq!(it.next(), Some(Ok(1)));
+        assert_eq!(it.next(), Some(Ok(300)));
+        assert_eq!(it.next(), Some(Err(DecodeError::UnexpectedEnd)));
+        assert_eq!(it.offset(), 3);
+        assert_eq!(it.remaining(), &[0xFF, 0xFF]);
+        assert_eq!(it.next(), None);
+
+        let mut it = u8::iter_var(&[0x05, 0x80, 0x02, 0x05]);
+        assert_eq!(it.next(), Some(Ok(5)));
+        assert_eq!(it.next(), Some(Err(DecodeError::OutOfRange)));
+        assert_eq!(it.next(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_length_delimited() {
+        let mut buf = Vec::new();
+        assert_eq!(buf.write_length_delimited(b"hello").unwrap(), 6);
+        assert_eq!(buf.write_length_delimited(b"").unwrap(), 1);
+        assert_eq!(buf.write_length_delimited(&[7; 300]).unwrap(), 302);
+
+        let mut reader: &[u8] = buf.as_ref();
+        let mut frame = Vec::new();
+        assert_eq!(reader.read_length_delimited(&mut frame, 300).unwrap(), 5);
+        assert_eq!(frame, b"hello");
+        frame.clear();
+        assert_eq!(reader.read_length_delimited(&mut frame, 300).unwrap(), 0);
+        assert!(frame.is_empty());
+        assert_eq!(reader.read_length_delimited(&mut frame, 300).unwrap(), 300);
+        assert_eq!(frame, vec![7; 300]);
+
+        let err = reader.read_length_delimited(&mut frame, 300).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_length_delimited_errors() {
+        let mut buf = Vec::new();
+        buf.write_length_delimited(&[1; 100]).unwrap();
+
+        let mut frame = Vec::new();
+        let err = buf
+            .as_slice()
+            .read_length_delimited(&mut frame, 99)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::FrameTooLarge { len: 100, max: 99 })
+        );
+        assert!(frame.is_empty());
+
+        // A truncated frame leaves `frame` as it was.
+        frame.extend_from_slice(b"prefix");
+        let err = buf[..50]
+            .as_ref()
+            .read_length_delimited(&mut frame, 100)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(frame, b"prefix");
+    }
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    #[tokio::test]
+    async fn test_async_length_delimited() {
+        let mut buf = Vec::new();
+        buf.write_length_delimited_async(b"hello").await.unwrap();
+        buf.write_length_delimited_async(&[7; 300]).await.unwrap();
+
+        let mut reader: &[u8] = buf.as_ref();
+        let mut frame = Vec::new();
+        assert_eq!(
+            reader
+                .read_length_delimited_async(&mut frame, 300)
+                .await
+                .unwrap(),
+            5
+        );
+        assert_eq!(frame, b"hello");
+        assert!(reader
+            .read_length_delimited_async(&mut frame, 299)
+            .await
+            .is_err());
+        assert_eq!(frame, b"hello");
+
+        let mut truncated = &buf[6..100];
+        assert_eq!(
+            truncated
+                .read_length_delimited_async(&mut frame, 300)
+                .await
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(frame, b"hello");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_processor_chunks() {
+        let values = [0_i64, -1, 300, i64::MIN, 1 << 40, i64::MAX];
+        let mut encoded = Vec::new();
+        encode_var_slice(&values, &mut encoded);
+
+        // Feed the input in chunks of all sizes; varints will span chunk boundaries.
+        for chunk_size in 1..=encoded.len() {
+            let mut p = VarIntProcessor::<i64>::new();
+            let mut decoded = Vec::new();
+            for mut chunk in encoded.chunks(chunk_size) {
+                while !chunk.is_empty() {
+                    let (consumed, v) = p.feed(chunk).unwrap();
+                    chunk = &chunk[consumed..];
+                    decoded.extend(v);
+                }
+            }
+            assert_eq!(decoded, values);
+            assert_eq!(p.buffered(), 0);
+        }
+    }
+
+    #[test]
+    fn test_processor_push_slice() {
+        let mut p = VarIntProcessor::<u32>::default();
+        assert_eq!(p.push_slice(&[]), Ok(0));
+        assert_eq!(p.decode(), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(p.push_slice(&[0xAC]), Ok(1));
+        assert!(!p.finished());
+        assert_eq!(p.push_slice(&[0x02, 0x05, 0x06]), Ok(1));
+        assert!(p.finished());
+        assert_eq!(p.push_slice(&[0x05]), Ok(0));
+        assert_eq!(p.buffered(), 2);
+        assert_eq!(p.decode(), Ok(300));
+        p.reset();
+        assert_eq!(p.buffered(), 0);
+
+        assert_eq!(p.feed(&[0x80, 0x00]), Ok((2, Some(0))));
+        assert_eq!(p.push_slice(&[0x80, 0x00]), Ok(2));
+        assert_eq!(p.decode_canonical(), Err(DecodeError::NonCanonical));
+    }
+
+    #[test]
+    fn test_processor_errors() {
+        let mut p = VarIntProcessor::<u16>::new();
+        assert_eq!(p.feed(&[0xFF; 4]), Err((3, DecodeError::Overflow)));
+
+        let mut p = VarIntProcessor::<u8>::new();
+        assert_eq!(p.feed(&[0xFF, 0x03]), Err((2, DecodeError::OutOfRange)));
+    }
+
+    #[test]
+    fn test_processor_resume_after_overflow() {
+        // A varint that is too long for u16, followed by 127 and 5.
+        let src = [0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x7F, 0x05];
+        let mut p = VarIntProcessor::<u16>::new();
+        let (consumed, err) = p.feed(&src).unwrap_err();
+        assert_eq!((consumed, err), (3, DecodeError::Overflow));
+
+        // Skip the rest of the invalid varint, then continue with the next one.
+        p.reset();
+        let mut rest = &src[consumed..];
+        while rest[0] & 0x80 != 0 {
+            rest = &rest[1..];
+        }
+        let rest = &rest[1..];
+        assert_eq!(p.feed(rest), Ok((1, Some(127))));
+        assert_eq!(p.feed(&rest[1..]), Ok((1, Some(5))));
+        assert_eq!(p.buffered(), 0);
+    }
 }
@@ -0,0 +1,212 @@
+//! Field keys of the protobuf wire format, see
+//! <https://protobuf.dev/programming-guides/encoding/#structure>.
+
+use core::convert::TryFrom;
+
+use crate::error::DecodeError;
+use crate::varint::VarInt;
+
+/// The largest field number allowed by protobuf, `2^29 - 1`.
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+/// How the value following a protobuf field key is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WireType {
+    /// A varint: `int32`, `int64`, `uint32`, `uint64`, `sint32`, `sint64`, `bool`, `enum`.
+    VarInt = 0,
+    /// Eight bytes: `fixed64`, `sfixed64`, `double`.
+    I64 = 1,
+    /// A varint length followed by that many bytes: `string`, `bytes`, embedded messages,
+    /// packed repeated fields.
+    LengthDelimited = 2,
+    /// Start of a group (deprecated).
+    StartGroup = 3,
+    /// End of a group (deprecated).
+    EndGroup = 4,
+    /// Four bytes: `fixed32`, `sfixed32`, `float`.
+    I32 = 5,
+}
+
+impl TryFrom<u8> for WireType {
+    type Error = DecodeError;
+
+    fn try_from(wire_type: u8) -> Result<WireType, DecodeError> {
+        match wire_type {
+            0 => Ok(WireType::VarInt),
+            1 => Ok(WireType::I64),
+            2 => Ok(WireType::LengthDelimited),
+            3 => Ok(WireType::StartGroup),
+            4 => Ok(WireType::EndGroup),
+            5 => Ok(WireType::I32),
+            _ => Err(DecodeError::InvalidWireType { wire_type }),
+        }
+    }
+}
+
+impl From<WireType> for u8 {
+    fn from(wire_type: WireType) -> u8 {
+        wire_type as u8
+    }
+}
+
+/// Combines field number and wire type into a key.
+///
+/// Panics if `field_number` is 0 or larger than [`MAX_FIELD_NUMBER`].
+pub(crate) fn make_key(field_number: u32, wire_type: WireType) -> u32 {
+    assert!(
+        field_number != 0 && field_number <= MAX_FIELD_NUMBER,
+        "invalid protobuf field number {}",
+        field_number
+    );
+    (field_number << 3) | wire_type as u32
+}
+
+/// Splits a key into field number and wire type.
+pub(crate) fn split_key(key: u32) -> Result<(u32, WireType), DecodeError> {
+    let wire_type = WireType::try_from((key & 0b111) as u8)?;
+    let field_number = key >> 3;
+    if field_number == 0 {
+        return Err(DecodeError::InvalidFieldNumber);
+    }
+    Ok((field_number, wire_type))
+}
+
+/// Returns the number of bytes the key for `field_number` needs in its encoded form.
+///
+/// Panics if `field_number` is 0 or larger than [`MAX_FIELD_NUMBER`].
+pub fn key_required_space(field_number: u32) -> usize {
+    make_key(field_number, WireType::VarInt).required_space()
+}
+
+/// Encodes the key `(field_number << 3) | wire_type` as varint into `dst`, which must be at least
+/// `key_required_space(field_number)` bytes long. Returns the number of bytes written.
+///
+/// Panics if `field_number` is 0 or larger than [`MAX_FIELD_NUMBER`].
+pub fn encode_key(field_number: u32, wire_type: WireType, dst: &mut [u8]) -> usize {
+    make_key(field_number, wire_type).encode_var(dst)
+}
+
+/// Decodes a key from the start of `src`. Returns the field number, the wire type and the number
+/// of bytes read.
+///
+/// Keys with an unknown wire type result in [`DecodeError::InvalidWireType`], keys with field
+/// number 0 in [`DecodeError::InvalidFieldNumber`].
+pub fn decode_key(src: &[u8]) -> Result<(u32, WireType, usize), DecodeError> {
+    let (key, size) = u32::try_decode_var(src)?;
+    let (field_number, wire_type) = split_key(key)?;
+    Ok((field_number, wire_type, size))
+}
+
+/// Returns the number of bytes a value of `wire_type` occupies, if it is of fixed size.
+pub(crate) fn fixed_size(wire_type: WireType) -> Option<usize> {
+    match wire_type {
+        WireType::I64 => Some(8),
+        WireType::I32 => Some(4),
+        _ => None,
+    }
+}
+
+/// The deepest nesting of groups accepted when skipping a group, like protobuf's default
+/// recursion limit.
+pub(crate) const MAX_GROUP_DEPTH: usize = 100;
+
+/// The field numbers of the groups being skipped whose end key hasn't been seen yet. This is kept
+/// in a fixed-size array, so that skipping fields doesn't need to allocate.
+pub(crate) struct GroupStack {
+    field_numbers: [u32; MAX_GROUP_DEPTH],
+    depth: usize,
+}
+
+impl GroupStack {
+    pub(crate) fn new() -> GroupStack {
+        GroupStack {
+            field_numbers: [0; MAX_GROUP_DEPTH],
+            depth: 0,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.depth == 0
+    }
+
+    /// Records a start key of a group.
+    pub(crate) fn start(&mut self, field_number: u32) -> Result<(), DecodeError> {
+        if self.depth == MAX_GROUP_DEPTH {
+            return Err(DecodeError::GroupTooDeep {
+                max: MAX_GROUP_DEPTH,
+            });
+        }
+        self.field_numbers[self.depth] = field_number;
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Checks an end key against the start key of the innermost group, and closes that group.
+    pub(crate) fn end(&mut self, field_number: u32) -> Result<(), DecodeError> {
+        if self.depth == 0 {
+            return Err(DecodeError::InvalidWireType {
+                wire_type: WireType::EndGroup.into(),
+            });
+        }
+        let expected = self.field_numbers[self.depth - 1];
+        if field_number != expected {
+            return Err(DecodeError::GroupMismatch {
+                expected,
+                actual: field_number,
+            });
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+}
+
+/// Skips the value of a field with `field_number` and `wire_type` at the start of `src`, e.g.
+/// because the field is unknown. Returns the number of bytes skipped. The key must already have
+/// been read.
+///
+/// A group ([`WireType::StartGroup`]) is skipped including all nested fields and its end key. The
+/// end key of every group must have the field number of its start key, otherwise
+/// [`DecodeError::GroupMismatch`] is returned; groups nested more than 100 deep result in
+/// [`DecodeError::GroupTooDeep`]. Skipping [`WireType::EndGroup`] without an enclosing group
+/// results in [`DecodeError::InvalidWireType`]; if `src` ends before the value,
+/// [`DecodeError::UnexpectedEnd`] is returned.
+pub fn skip_field(
+    field_number: u32,
+    wire_type: WireType,
+    src: &[u8],
+) -> Result<usize, DecodeError> {
+    let mut field_number = field_number;
+    let mut wire_type = wire_type;
+    let mut i = 0;
+    let mut groups = GroupStack::new();
+
+    loop {
+        match wire_type {
+            WireType::VarInt => i += u64::try_decode_var(&src[i..])?.1,
+            WireType::I64 | WireType::I32 | WireType::LengthDelimited => {
+                let len = match fixed_size(wire_type) {
+                    Some(len) => len,
+                    None => {
+                        let (len, size) = u64::try_decode_var(&src[i..])?;
+                        i += size;
+                        usize::try_from(len).map_err(|_| DecodeError::UnexpectedEnd)?
+                    }
+                };
+                if len > src.len() - i {
+                    return Err(DecodeError::UnexpectedEnd);
+                }
+                i += len;
+            }
+            WireType::StartGroup => groups.start(field_number)?,
+            WireType::EndGroup => groups.end(field_number)?,
+        }
+
+        if groups.is_empty() {
+            return Ok(i);
+        }
+        let (next_number, next_type, size) = decode_key(&src[i..])?;
+        field_number = next_number;
+        wire_type = next_type;
+        i += size;
+    }
+}
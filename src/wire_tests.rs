@@ -0,0 +1,309 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    use std::io;
+
+    use core::convert::TryFrom;
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::reader::VarIntAsyncReader;
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    use crate::writer::VarIntAsyncWriter;
+
+    use crate::error::DecodeError;
+    #[cfg(feature = "std")]
+    use crate::reader::VarIntReader;
+    use crate::wire::{
+        decode_key, encode_key, key_required_space, skip_field, WireType, MAX_FIELD_NUMBER,
+    };
+    #[cfg(feature = "std")]
+    use crate::writer::VarIntWriter;
+
+    #[test]
+    fn test_wire_type() {
+        for wt in 0..6 {
+            assert_eq!(u8::from(WireType::try_from(wt).unwrap()), wt);
+        }
+        assert_eq!(
+            WireType::try_from(6),
+            Err(DecodeError::InvalidWireType { wire_type: 6 })
+        );
+    }
+
+    #[test]
+    fn test_encode_key() {
+        let mut buf = [0_u8; 5];
+        // Examples from the protobuf documentation.
+        assert_eq!(encode_key(1, WireType::VarInt, &mut buf), 1);
+        assert_eq!(buf[0], 0x08);
+        assert_eq!(encode_key(2, WireType::LengthDelimited, &mut buf), 1);
+        assert_eq!(buf[0], 0x12);
+        assert_eq!(encode_key(16, WireType::I32, &mut buf), 2);
+        assert_eq!(&buf[..2], &[0x85, 0x01]);
+
+        assert_eq!(key_required_space(15), 1);
+        assert_eq!(key_required_space(16), 2);
+        assert_eq!(key_required_space(MAX_FIELD_NUMBER), 5);
+        assert_eq!(
+            encode_key(MAX_FIELD_NUMBER, WireType::EndGroup, &mut buf),
+            5
+        );
+        assert_eq!(
+            decode_key(&buf),
+            Ok((MAX_FIELD_NUMBER, WireType::EndGroup, 5))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_encode_key_zero() {
+        encode_key(0, WireType::VarInt, &mut [0_u8; 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_encode_key_too_large() {
+        encode_key(MAX_FIELD_NUMBER + 1, WireType::VarInt, &mut [0_u8; 5]);
+    }
+
+    #[test]
+    fn test_decode_key() {
+        assert_eq!(decode_key(&[0x08, 0x96]), Ok((1, WireType::VarInt, 1)));
+        assert_eq!(decode_key(&[0x85, 0x01]), Ok((16, WireType::I32, 2)));
+        assert_eq!(decode_key(&[0x85]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(
+            decode_key(&[0x0E]),
+            Err(DecodeError::InvalidWireType { wire_type: 6 })
+        );
+        assert_eq!(decode_key(&[0x02]), Err(DecodeError::InvalidFieldNumber));
+        // Larger than any u32.
+        assert_eq!(
+            decode_key(&[0xFF, 0xFF, 0xFF, 0xFF, 0x10]),
+            Err(DecodeError::OutOfRange)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_writer() {
+        let mut buf = vec![];
+        assert_eq!(buf.write_key(1, WireType::VarInt).unwrap(), 1);
+        assert_eq!(buf.write_key(300, WireType::I64).unwrap(), 2);
+        buf.push(0x07);
+        assert_eq!(buf, vec![0x08, 0xE1, 0x12, 0x07]);
+
+        let mut r: &[u8] = &buf;
+        assert_eq!(r.read_key().unwrap(), (1, WireType::VarInt));
+        assert_eq!(r.read_key().unwrap(), (300, WireType::I64));
+        let e = r.read_key().unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            e.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::InvalidWireType { wire_type: 7 })
+        );
+        assert_eq!(
+            r.read_key().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    #[tokio::test]
+    async fn test_async_reader_writer() {
+        let mut buf = vec![];
+        buf.write_key_async(1, WireType::VarInt).await.unwrap();
+        buf.write_key_async(300, WireType::LengthDelimited)
+            .await
+            .unwrap();
+        assert_eq!(buf, vec![0x08, 0xE2, 0x12]);
+
+        let mut r: &[u8] = &buf;
+        assert_eq!(r.read_key_async().await.unwrap(), (1, WireType::VarInt));
+        assert_eq!(
+            r.read_key_async().await.unwrap(),
+            (300, WireType::LengthDelimited)
+        );
+    }
+
+    /// A message containing one field of each wire type, followed by the key of field 15.
+    #[cfg(feature = "std")]
+    fn message() -> Vec<u8> {
+        let mut buf = vec![];
+        buf.write_key(1, WireType::VarInt).unwrap();
+        buf.write_varint(u64::MAX).unwrap();
+        buf.write_key(2, WireType::I64).unwrap();
+        buf.extend_from_slice(&[0; 8]);
+        buf.write_key(3, WireType::LengthDelimited).unwrap();
+        buf.write_length_delimited(&[1; 300]).unwrap();
+        buf.write_key(4, WireType::I32).unwrap();
+        buf.extend_from_slice(&[0; 4]);
+        buf.write_key(5, WireType::StartGroup).unwrap();
+        buf.write_key(6, WireType::VarInt).unwrap();
+        buf.write_varint(1_u32).unwrap();
+        buf.write_key(7, WireType::StartGroup).unwrap();
+        buf.write_key(7, WireType::EndGroup).unwrap();
+        buf.write_key(5, WireType::EndGroup).unwrap();
+        buf.write_key(15, WireType::VarInt).unwrap();
+        buf
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_skip_field() {
+        let buf = message();
+        let mut i = 0;
+        for field in 1..=5 {
+            let (number, wire_type, size) = decode_key(&buf[i..]).unwrap();
+            assert_eq!(number, field);
+            i += size;
+            i += skip_field(number, wire_type, &buf[i..]).unwrap();
+        }
+        assert_eq!(decode_key(&buf[i..]), Ok((15, WireType::VarInt, 1)));
+
+        let mut r: &[u8] = &buf;
+        for field in 1..=5 {
+            let (number, wire_type) = r.read_key().unwrap();
+            assert_eq!(number, field);
+            r.skip_field(number, wire_type).unwrap();
+        }
+        assert_eq!(r.read_key().unwrap(), (15, WireType::VarInt));
+    }
+
+    #[test]
+    fn test_skip_field_errors() {
+        assert_eq!(
+            skip_field(1, WireType::EndGroup, &[]),
+            Err(DecodeError::InvalidWireType { wire_type: 4 })
+        );
+        assert_eq!(
+            skip_field(1, WireType::VarInt, &[0xFF; 11]),
+            Err(DecodeError::Overflow)
+        );
+        // Group containing an invalid key.
+        assert_eq!(
+            skip_field(1, WireType::StartGroup, &[0x0F]),
+            Err(DecodeError::InvalidWireType { wire_type: 7 })
+        );
+
+        // End keys must match the start key of the innermost group. This is a group of field 2
+        // followed by two end keys of field 1.
+        let buf = [0x13, 0x0C, 0x0C];
+        assert_eq!(
+            skip_field(1, WireType::StartGroup, &buf),
+            Err(DecodeError::GroupMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            skip_field(3, WireType::StartGroup, &buf[2..]),
+            Err(DecodeError::GroupMismatch {
+                expected: 3,
+                actual: 1
+            })
+        );
+
+        // Groups nested 100 deep are fine, one more level is not.
+        let mut buf = [0x0C; 201];
+        buf[..99].fill(0x0B);
+        assert_eq!(skip_field(1, WireType::StartGroup, &buf[..199]), Ok(199));
+        buf[99] = 0x0B;
+        assert_eq!(
+            skip_field(1, WireType::StartGroup, &buf),
+            Err(DecodeError::GroupTooDeep { max: 100 })
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_skip_field_errors() {
+        let e = (&[0x0F_u8][..])
+            .skip_field(1, WireType::StartGroup)
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        let e = (&[][..]).skip_field(1, WireType::EndGroup).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+        let e = (&[0x13_u8, 0x0C, 0x0C][..])
+            .skip_field(1, WireType::StartGroup)
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            e.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::GroupMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+
+        let mut buf = [0x0C; 201];
+        buf[..99].fill(0x0B);
+        (&buf[..199]).skip_field(1, WireType::StartGroup).unwrap();
+        buf[99] = 0x0B;
+        let e = (&buf[..]).skip_field(1, WireType::StartGroup).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+        // Every truncation of the message is detected.
+        let buf = message();
+        let end = buf.len() - 1;
+        for len in 0..end {
+            let mut truncated = &buf[..len];
+            let result = (|| {
+                let mut i = 0;
+                while i < truncated.len() {
+                    let (number, wire_type, size) = decode_key(&truncated[i..])?;
+                    i += size;
+                    i += skip_field(number, wire_type, &truncated[i..])?;
+                }
+                Ok(i)
+            })();
+            // Truncations at field boundaries are valid messages.
+            if ![0, 11, 20, 323, 328].contains(&len) {
+                assert_eq!(result, Err(DecodeError::UnexpectedEnd), "{}", len);
+            }
+
+            let mut result = Ok(());
+            while !truncated.is_empty() && result.is_ok() {
+                result = truncated
+                    .read_key()
+                    .and_then(|(number, wire_type)| truncated.skip_field(number, wire_type));
+            }
+            if ![0, 11, 20, 323, 328].contains(&len) {
+                assert_eq!(
+                    result.unwrap_err().kind(),
+                    io::ErrorKind::UnexpectedEof,
+                    "{}",
+                    len
+                );
+            }
+        }
+    }
+
+    #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+    #[tokio::test]
+    async fn test_async_skip_field() {
+        let buf = message();
+        let mut r: &[u8] = &buf;
+        for field in 1..=5 {
+            let (number, wire_type) = r.read_key_async().await.unwrap();
+            assert_eq!(number, field);
+            r.skip_field_async(number, wire_type).await.unwrap();
+        }
+        assert_eq!(r.read_key_async().await.unwrap(), (15, WireType::VarInt));
+
+        let mut r: &[u8] = &buf[..buf.len() - 4];
+        for _ in 1..=4 {
+            let (number, wire_type) = r.read_key_async().await.unwrap();
+            r.skip_field_async(number, wire_type).await.unwrap();
+        }
+        let (number, wire_type) = r.read_key_async().await.unwrap();
+        assert!(r.skip_field_async(number, wire_type).await.is_err());
+
+        let mut r: &[u8] = &[0x13, 0x0C];
+        let e = r
+            .skip_field_async(1, WireType::StartGroup)
+            .await
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,7 +1,10 @@
 use std::io::{Result, Write};
 
 use crate::fixed::FixedInt;
-use crate::varint::VarInt;
+use crate::leb128::Leb128;
+use crate::prefix_varint::{PrefixVarInt, MAX_PREFIX_VARINT_SIZE};
+use crate::varint::{VarInt, MAX_VARINT_SIZE};
+use crate::wire::{make_key, WireType};
 
 #[cfg(feature = "tokio_async")]
 use tokio::io::{AsyncWrite, AsyncWriteExt};
@@ -13,6 +16,27 @@ use futures_util::{io::AsyncWrite, io::AsyncWriteExt};
 /// writing into one step.
 pub trait VarIntWriter {
     fn write_varint<VI: VarInt>(&mut self, n: VI) -> Result<usize>;
+
+    /// Writes `data` prefixed by its length as varint, so that it can be read back using
+    /// [`VarIntReader::read_length_delimited()`](crate::VarIntReader::read_length_delimited).
+    /// Returns the total number of bytes written.
+    fn write_length_delimited(&mut self, data: &[u8]) -> Result<usize>;
+
+    /// Writes the protobuf field key for `field_number` and `wire_type` (see
+    /// [`encode_key()`](crate::encode_key)). Returns the number of bytes written.
+    ///
+    /// Panics if `field_number` is 0 or larger than [`MAX_FIELD_NUMBER`](crate::MAX_FIELD_NUMBER).
+    fn write_key(&mut self, field_number: u32, wire_type: WireType) -> Result<usize>;
+
+    /// Writes `values` as packed repeated field of varints: their total length in bytes as
+    /// varint, followed by the values. Returns the number of bytes written.
+    ///
+    /// The values are iterated twice, first to compute the length prefix.
+    fn write_packed_varint<VI, I>(&mut self, values: I) -> Result<usize>
+    where
+        VI: VarInt,
+        I: IntoIterator<Item = VI>,
+        I::IntoIter: Clone;
 }
 
 /// Like VarIntWriter, but asynchronous.
@@ -21,57 +45,269 @@ pub trait VarIntWriter {
 pub trait VarIntAsyncWriter {
     /// Write a VarInt integer to an asynchronous writer.
     async fn write_varint_async<VI: VarInt>(&mut self, n: VI) -> Result<usize>;
+    /// Like `write_length_delimited()`, but asynchronous.
+    async fn write_length_delimited_async(&mut self, data: &[u8]) -> Result<usize>;
+    /// Like `write_key()`, but asynchronous.
+    async fn write_key_async(&mut self, field_number: u32, wire_type: WireType) -> Result<usize>;
+    /// Like `write_packed_varint()`, but asynchronous.
+    async fn write_packed_varint_async<VI, I>(&mut self, values: I) -> Result<usize>
+    where
+        VI: VarInt,
+        I: IntoIterator<Item = VI>,
+        I::IntoIter: Clone;
 }
 
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
 #[async_trait::async_trait(?Send)]
 impl<AW: AsyncWrite + Unpin> VarIntAsyncWriter for AW {
     async fn write_varint_async<VI: VarInt>(&mut self, n: VI) -> Result<usize> {
-        let mut buf = [0_u8; 10];
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
         let b = n.encode_var(&mut buf);
         self.write_all(&buf[0..b]).await?;
         Ok(b)
     }
+
+    async fn write_length_delimited_async(&mut self, data: &[u8]) -> Result<usize> {
+        let prefix = self.write_varint_async(data.len()).await?;
+        self.write_all(data).await?;
+        Ok(prefix + data.len())
+    }
+
+    async fn write_key_async(&mut self, field_number: u32, wire_type: WireType) -> Result<usize> {
+        self.write_varint_async(make_key(field_number, wire_type))
+            .await
+    }
+
+    async fn write_packed_varint_async<VI, I>(&mut self, values: I) -> Result<usize>
+    where
+        VI: VarInt,
+        I: IntoIterator<Item = VI>,
+        I::IntoIter: Clone,
+    {
+        let values = values.into_iter();
+        let len: usize = values.clone().map(VI::required_space).sum();
+        let prefix = self.write_varint_async(len).await?;
+        for v in values {
+            self.write_varint_async(v).await?;
+        }
+        Ok(prefix + len)
+    }
 }
 
 impl<Inner: Write> VarIntWriter for Inner {
     fn write_varint<VI: VarInt>(&mut self, n: VI) -> Result<usize> {
-        let mut buf = [0_u8; 10];
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
         let used = n.encode_var(&mut buf[..]);
 
         self.write_all(&buf[0..used])?;
         Ok(used)
     }
+
+    fn write_length_delimited(&mut self, data: &[u8]) -> Result<usize> {
+        let prefix = self.write_varint(data.len())?;
+        self.write_all(data)?;
+        Ok(prefix + data.len())
+    }
+
+    fn write_key(&mut self, field_number: u32, wire_type: WireType) -> Result<usize> {
+        self.write_varint(make_key(field_number, wire_type))
+    }
+
+    fn write_packed_varint<VI, I>(&mut self, values: I) -> Result<usize>
+    where
+        VI: VarInt,
+        I: IntoIterator<Item = VI>,
+        I::IntoIter: Clone,
+    {
+        let values = values.into_iter();
+        let len: usize = values.clone().map(VI::required_space).sum();
+        let prefix = self.write_varint(len)?;
+        for v in values {
+            self.write_varint(v)?;
+        }
+        Ok(prefix + len)
+    }
 }
 
 /// A trait for writing integers without encoding (i.e. `FixedInt`) to any `Write` type.
 pub trait FixedIntWriter {
     fn write_fixedint<FI: FixedInt>(&mut self, n: FI) -> Result<usize>;
+    /// Like `write_fixedint()`, but writes the integer in big-endian order.
+    fn write_fixedint_be<FI: FixedInt>(&mut self, n: FI) -> Result<usize>;
+
+    /// Writes `values` as packed repeated field of little-endian fixed integers: their total
+    /// length in bytes as varint, followed by the values. Returns the number of bytes written.
+    fn write_packed_fixed<FI, I>(&mut self, values: I) -> Result<usize>
+    where
+        FI: FixedInt,
+        I: IntoIterator<Item = FI>,
+        I::IntoIter: Clone;
 }
 
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
 #[async_trait::async_trait(?Send)]
 pub trait FixedIntAsyncWriter {
     async fn write_fixedint_async<FI: FixedInt>(&mut self, n: FI) -> Result<usize>;
+    /// Like `write_fixedint_async()`, but writes the integer in big-endian order.
+    async fn write_fixedint_be_async<FI: FixedInt>(&mut self, n: FI) -> Result<usize>;
+    /// Like `write_packed_fixed()`, but asynchronous.
+    async fn write_packed_fixed_async<FI, I>(&mut self, values: I) -> Result<usize>
+    where
+        FI: FixedInt,
+        I: IntoIterator<Item = FI>,
+        I::IntoIter: Clone;
 }
 
 #[cfg(any(feature = "tokio_async", feature = "futures_async"))]
 #[async_trait::async_trait(?Send)]
 impl<AW: AsyncWrite + Unpin> FixedIntAsyncWriter for AW {
     async fn write_fixedint_async<FI: FixedInt>(&mut self, n: FI) -> Result<usize> {
-        let mut buf = [0_u8; 8];
+        let mut buf = [0_u8; 16];
         n.encode_fixed(&mut buf[..size_of::<FI>()]);
         self.write_all(&buf[..size_of::<FI>()]).await?;
         Ok(size_of::<FI>())
     }
+
+    async fn write_fixedint_be_async<FI: FixedInt>(&mut self, n: FI) -> Result<usize> {
+        let mut buf = [0_u8; 16];
+        n.encode_fixed_be(&mut buf[..size_of::<FI>()]);
+        self.write_all(&buf[..size_of::<FI>()]).await?;
+        Ok(size_of::<FI>())
+    }
+
+    async fn write_packed_fixed_async<FI, I>(&mut self, values: I) -> Result<usize>
+    where
+        FI: FixedInt,
+        I: IntoIterator<Item = FI>,
+        I::IntoIter: Clone,
+    {
+        let values = values.into_iter();
+        let len = values.clone().count() * FI::ENCODED_SIZE;
+        let prefix = self.write_varint_async(len).await?;
+        for v in values {
+            self.write_all(v.encode_fixed_light().as_ref()).await?;
+        }
+        Ok(prefix + len)
+    }
 }
 
 impl<W: Write> FixedIntWriter for W {
     fn write_fixedint<FI: FixedInt>(&mut self, n: FI) -> Result<usize> {
-        let mut buf = [0_u8; 8];
+        let mut buf = [0_u8; 16];
         n.encode_fixed(&mut buf[..size_of::<FI>()]);
 
         self.write_all(&buf[..size_of::<FI>()])?;
         Ok(size_of::<FI>())
     }
+
+    fn write_fixedint_be<FI: FixedInt>(&mut self, n: FI) -> Result<usize> {
+        let mut buf = [0_u8; 16];
+        n.encode_fixed_be(&mut buf[..size_of::<FI>()]);
+
+        self.write_all(&buf[..size_of::<FI>()])?;
+        Ok(size_of::<FI>())
+    }
+
+    fn write_packed_fixed<FI, I>(&mut self, values: I) -> Result<usize>
+    where
+        FI: FixedInt,
+        I: IntoIterator<Item = FI>,
+        I::IntoIter: Clone,
+    {
+        let values = values.into_iter();
+        let len = values.clone().count() * FI::ENCODED_SIZE;
+        let prefix = self.write_varint(len)?;
+        for v in values {
+            self.write_all(v.encode_fixed_light().as_ref())?;
+        }
+        Ok(prefix + len)
+    }
+}
+
+/// A trait for writing integers in [`Leb128`] encoding to any [`Write`] type.
+pub trait Leb128Writer {
+    /// Writes the shortest LEB128 encoding of `n`. Returns the number of bytes written.
+    fn write_leb128<L: Leb128>(&mut self, n: L) -> Result<usize>;
+    /// Writes `n` padded to exactly `len` bytes (see [`Leb128::encode_leb128_padded()`]).
+    /// Returns `len`.
+    ///
+    /// Panics if `n` doesn't fit into `len` bytes, or `len` is larger than
+    /// `L::MAX_ENCODED_SIZE`.
+    fn write_leb128_padded<L: Leb128>(&mut self, n: L, len: usize) -> Result<usize>;
+}
+
+/// Like Leb128Writer, but asynchronous.
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+#[async_trait::async_trait(?Send)]
+pub trait Leb128AsyncWriter {
+    async fn write_leb128_async<L: Leb128>(&mut self, n: L) -> Result<usize>;
+    /// Like `write_leb128_padded()`, but asynchronous.
+    async fn write_leb128_padded_async<L: Leb128>(&mut self, n: L, len: usize) -> Result<usize>;
+}
+
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+#[async_trait::async_trait(?Send)]
+impl<AW: AsyncWrite + Unpin> Leb128AsyncWriter for AW {
+    async fn write_leb128_async<L: Leb128>(&mut self, n: L) -> Result<usize> {
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
+        let b = n.encode_leb128(&mut buf);
+        self.write_all(&buf[..b]).await?;
+        Ok(b)
+    }
+
+    async fn write_leb128_padded_async<L: Leb128>(&mut self, n: L, len: usize) -> Result<usize> {
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
+        let b = n.encode_leb128_padded(&mut buf, len);
+        self.write_all(&buf[..b]).await?;
+        Ok(b)
+    }
+}
+
+impl<W: Write> Leb128Writer for W {
+    fn write_leb128<L: Leb128>(&mut self, n: L) -> Result<usize> {
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
+        let b = n.encode_leb128(&mut buf);
+        self.write_all(&buf[..b])?;
+        Ok(b)
+    }
+
+    fn write_leb128_padded<L: Leb128>(&mut self, n: L, len: usize) -> Result<usize> {
+        let mut buf = [0_u8; MAX_VARINT_SIZE];
+        let b = n.encode_leb128_padded(&mut buf, len);
+        self.write_all(&buf[..b])?;
+        Ok(b)
+    }
+}
+
+/// A trait for writing integers in [`PrefixVarInt`] encoding to any [`Write`] type.
+pub trait PrefixVarIntWriter {
+    /// Writes `n` as prefix varint. Returns the number of bytes written.
+    fn write_prefix_varint<PV: PrefixVarInt>(&mut self, n: PV) -> Result<usize>;
+}
+
+/// Like PrefixVarIntWriter, but asynchronous.
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+#[async_trait::async_trait(?Send)]
+pub trait PrefixVarIntAsyncWriter {
+    async fn write_prefix_varint_async<PV: PrefixVarInt>(&mut self, n: PV) -> Result<usize>;
+}
+
+#[cfg(any(feature = "tokio_async", feature = "futures_async"))]
+#[async_trait::async_trait(?Send)]
+impl<AW: AsyncWrite + Unpin> PrefixVarIntAsyncWriter for AW {
+    async fn write_prefix_varint_async<PV: PrefixVarInt>(&mut self, n: PV) -> Result<usize> {
+        let mut buf = [0_u8; MAX_PREFIX_VARINT_SIZE];
+        let b = n.encode_prefix_var(&mut buf);
+        self.write_all(&buf[..b]).await?;
+        Ok(b)
+    }
+}
+
+impl<W: Write> PrefixVarIntWriter for W {
+    fn write_prefix_varint<PV: PrefixVarInt>(&mut self, n: PV) -> Result<usize> {
+        let mut buf = [0_u8; MAX_PREFIX_VARINT_SIZE];
+        let b = n.encode_prefix_var(&mut buf);
+        self.write_all(&buf[..b])?;
+        Ok(b)
+    }
 }
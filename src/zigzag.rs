@@ -0,0 +1,52 @@
+/// Zigzag encoding of signed integers, as described in
+/// <https://developers.google.com/protocol-buffers/docs/encoding#signed-ints>.
+///
+/// Zigzag encoding maps signed integers to unsigned integers of the same width so that values
+/// with a small absolute value have a small encoding: 0 becomes 0, -1 becomes 1, 1 becomes 2, -2
+/// becomes 3, and so on. [`VarInt`](crate::VarInt) uses it for all signed integers, but it is
+/// also useful on its own, e.g. for delta coding or before encoding as
+/// [`FixedInt`](crate::FixedInt).
+///
+/// ```
+/// use integer_encoding::ZigZag;
+///
+/// assert_eq!((-2_i32).zigzag_encode(), 3_u32);
+/// assert_eq!(i32::zigzag_decode(3), -2);
+/// ```
+pub trait ZigZag: Sized + Copy {
+    /// The unsigned integer type of the same width.
+    type Unsigned;
+
+    /// Maps `self` to its zigzag-encoded unsigned representation.
+    fn zigzag_encode(self) -> Self::Unsigned;
+    /// Maps a zigzag-encoded value back to the signed integer it represents.
+    fn zigzag_decode(from: Self::Unsigned) -> Self;
+}
+
+macro_rules! impl_zigzag {
+    ($t:ty, $u:ty) => {
+        impl ZigZag for $t {
+            type Unsigned = $u;
+
+            #[inline]
+            fn zigzag_encode(self) -> $u {
+                ((self << 1) ^ (self >> (<$t>::BITS - 1))) as $u
+            }
+
+            // see: http://stackoverflow.com/a/2211086/56332
+            // casting required because operations like unary negation
+            // cannot be performed on unsigned integers
+            #[inline]
+            fn zigzag_decode(from: $u) -> $t {
+                ((from >> 1) ^ (-((from & 1) as $t)) as $u) as $t
+            }
+        }
+    };
+}
+
+impl_zigzag!(i8, u8);
+impl_zigzag!(i16, u16);
+impl_zigzag!(i32, u32);
+impl_zigzag!(i64, u64);
+impl_zigzag!(i128, u128);
+impl_zigzag!(isize, usize);
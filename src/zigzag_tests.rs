@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use crate::fixed::FixedInt;
+    use crate::varint::VarInt;
+    use crate::zigzag::ZigZag;
+
+    #[test]
+    fn test_zigzag_encode() {
+        assert_eq!(0_i32.zigzag_encode(), 0_u32);
+        assert_eq!((-1_i32).zigzag_encode(), 1);
+        assert_eq!(1_i32.zigzag_encode(), 2);
+        assert_eq!((-2_i32).zigzag_encode(), 3);
+        assert_eq!(i32::MAX.zigzag_encode(), u32::MAX - 1);
+        assert_eq!(i32::MIN.zigzag_encode(), u32::MAX);
+
+        assert_eq!(i8::MIN.zigzag_encode(), u8::MAX);
+        assert_eq!(i16::MIN.zigzag_encode(), u16::MAX);
+        assert_eq!(i64::MIN.zigzag_encode(), u64::MAX);
+        assert_eq!(i128::MIN.zigzag_encode(), u128::MAX);
+        assert_eq!(isize::MIN.zigzag_encode(), usize::MAX);
+    }
+
+    fn roundtrip<T: ZigZag + PartialEq + core::fmt::Debug>(values: &[T]) {
+        for &v in values {
+            assert_eq!(T::zigzag_decode(v.zigzag_encode()), v);
+        }
+    }
+
+    #[test]
+    fn test_zigzag_roundtrip() {
+        roundtrip(&[0_i8, 1, -1, 63, -64, i8::MAX, i8::MIN]);
+        roundtrip(&[0_i16, 300, -300, i16::MAX, i16::MIN]);
+        roundtrip(&[0_i32, 70000, -70000, i32::MAX, i32::MIN]);
+        roundtrip(&[0_i64, 1 << 40, -(1 << 40), i64::MAX, i64::MIN]);
+        roundtrip(&[0_i128, 1 << 100, -(1 << 100), i128::MAX, i128::MIN]);
+        roundtrip(&[0_isize, -5, isize::MAX, isize::MIN]);
+
+        for u in 0..=u8::MAX {
+            assert_eq!(i8::zigzag_decode(u).zigzag_encode(), u);
+        }
+    }
+
+    #[test]
+    fn test_zigzag_matches_varint() {
+        let (mut signed, mut unsigned) = ([0_u8; 10], [0_u8; 10]);
+        for &v in &[0_i64, -1, 1, -64, 64, i64::MAX, i64::MIN] {
+            let len = v.encode_var(&mut signed);
+            assert_eq!(v.zigzag_encode().encode_var(&mut unsigned), len);
+            assert_eq!(signed[..len], unsigned[..len]);
+        }
+    }
+
+    #[test]
+    fn test_zigzag_fixed() {
+        // Zigzag-encoded fixed-width integers, e.g. for small deltas.
+        let encoded = (-3_i16).zigzag_encode().encode_fixed_light();
+        assert_eq!(encoded, [5, 0]);
+        assert_eq!(i16::zigzag_decode(u16::decode_fixed(&encoded).unwrap()), -3);
+    }
+}